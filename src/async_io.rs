@@ -0,0 +1,772 @@
+//! Asynchronous counterparts of [`crate::SafeRead`] and [`crate::SafeWrite`] (feature `tokio`)
+//!
+//! Traits are implemented for every tokio [`AsyncRead`]/[`AsyncWrite`], seekable or not, so
+//! they work on sockets and pipes. Without seeking there is no position to report, so errors
+//! don't name stream offsets. Methods are called `get_*`/`put_*` rather than `read_*`/`write_*`,
+//! so they don't clash with tokio's [`AsyncReadExt`] and [`AsyncWriteExt`] (`read_u8`,
+//! `write_u16_le`, ...) or with the sync traits on types implementing both. The module isn't
+//! re-exported from crate root.
+//!
+//! Streams have to be [`Send`], and so are all returned futures, so they can be awaited in
+//! spawned tasks of a multi-threaded runtime.
+//!
+//! ```
+//! use pakr_typed_io::async_io::{be::AsyncSafeReader, AsyncSafeRead, AsyncSafeWrite};
+//! // Can be imported alongside, no method is ambiguous
+//! use tokio::io::{AsyncReadExt, AsyncWriteExt};
+//!
+//! // Generic code can hand the stream over to another task
+//! async fn read_magic<R>(mut rd: R) -> Result<u32, eyre::Report>
+//! where
+//!     R: AsyncSafeRead + 'static,
+//! {
+//!     tokio::spawn(async move { rd.get_u32be().await }).await?
+//! }
+//!
+//! #[tokio::main(flavor = "current_thread")]
+//! async fn main() -> Result<(), eyre::Report> {
+//!     let (mut tx, rx) = tokio::io::duplex(64);
+//!
+//!     tx.put_u32_be(0xDEAD_BEEF).await?;
+//!     tx.put_leb128(300).await?;
+//!     tx.put_cstr("hello").await?;
+//!     tx.flush().await?;
+//!     drop(tx);
+//!
+//!     let mut rx = tokio::io::BufReader::new(rx);
+//!     let magic: u32 = rx.get_auto().await?;
+//!     assert_eq!(0xDEAD_BEEF, magic);
+//!     assert_eq!(300, rx.get_leb128().await?);
+//!     assert_eq!(b'h', rx.peek_u8().await?);
+//!     assert_eq!("hello", rx.get_cstr_utf8().await?);
+//!     assert!(rx.get_u8().await.is_err());
+//!     assert!(rx.read_u8().await.is_err());
+//!
+//!     // Length prefix is checked before anything is allocated
+//!     let (mut tx, mut rx) = tokio::io::duplex(64);
+//!     tx.put_u32_be(0xFFFF_FFFF).await?;
+//!     assert_eq!(
+//!         rx.get_string_u32be_max(0x100).await.unwrap_err().to_string(),
+//!         "string claims 0xFFFFFFFF bytes, more than 0x100 allowed"
+//!     );
+//!
+//!     let (mut tx, rx) = tokio::io::duplex(64);
+//!     tx.put_u32_be(0xCAFE_F00D).await?;
+//!     assert_eq!(0xCAFE_F00D, read_magic(rx).await?);
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! Strings come in the same encodings as with sync traits.
+//!
+//! ```
+//! use pakr_typed_io::async_io::{AsyncSafeRead, AsyncSafeWrite};
+//! use pakr_typed_io::Padding;
+//!
+//! #[tokio::main(flavor = "current_thread")]
+//! async fn main() -> Result<(), eyre::Report> {
+//!     let (mut tx, mut rx) = tokio::io::duplex(256);
+//!
+//!     tx.put_string_leb128("varint").await?;
+//!     tx.put_fixed_str("NAME", 6, Padding::Space).await?;
+//!     tx.put_utf16_le_cstr("Ωmega").await?;
+//!     tx.put_utf16_be_prefixed("BE").await?;
+//!     tx.put_mutf8("a\0b").await?;
+//!     tx.put_latin1("café").await?;
+//!     drop(tx);
+//!
+//!     assert_eq!("varint", rx.get_string_leb128().await?);
+//!     assert_eq!("NAME", rx.get_fixed_str(6, Padding::Space).await?);
+//!     assert_eq!("Ωmega", rx.get_utf16le_cstr().await?);
+//!     assert_eq!("BE", rx.get_utf16be_prefixed().await?);
+//!     assert_eq!("a\0b", rx.get_mutf8(4).await?);
+//!     assert_eq!("café", rx.get_latin1(4).await?);
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use std::future::Future;
+
+use eyre::{eyre, Result, WrapErr};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::{
+    text::{decode_latin1, decode_mutf8, decode_utf16, encode_latin1, encode_mutf8},
+    Padding,
+};
+
+macro_rules! read_int {
+    ($name:ident, $typ:ty, $conv:ident) => {
+        fn $name(&mut self) -> impl Future<Output = Result<$typ>> + Send {
+            async move {
+                let mut buf = [0u8; std::mem::size_of::<$typ>()];
+                self.read_exact(&mut buf).await?;
+                Ok(<$typ>::$conv(buf))
+            }
+        }
+    };
+}
+
+macro_rules! write_int {
+    ($name:ident, $typ:ty, $conv:ident) => {
+        fn $name(&mut self, val: $typ) -> impl Future<Output = Result<()>> + Send {
+            async move {
+                self.write_all(&val.$conv()).await?;
+                Ok(())
+            }
+        }
+    };
+}
+
+// Buffer grows with data actually received, not with the declared size
+async fn sized<R>(src: &mut R, size: u64) -> Result<Vec<u8>>
+where
+    R: AsyncRead + Unpin + ?Sized,
+{
+    let mut buf = Vec::new();
+    src.take(size).read_to_end(&mut buf).await?;
+    if (buf.len() as u64) < size {
+        return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+    }
+    Ok(buf)
+}
+
+async fn sized_str<R>(src: &mut R, size: u128, max: usize) -> Result<String>
+where
+    R: AsyncRead + Unpin + ?Sized,
+{
+    if size > max as u128 {
+        return Err(eyre!("string claims 0x{size:X} bytes, more than 0x{max:X} allowed"));
+    }
+    utf8(sized(src, size as u64).await?)
+}
+
+fn utf8(bytes: Vec<u8>) -> Result<String> {
+    String::from_utf8(bytes).map_err(|e| eyre!("invalid UTF-8 in string: {}", e.utf8_error()))
+}
+
+fn utf16(bytes: &[u8], conv: fn([u8; 2]) -> u16) -> Result<String> {
+    let units: Vec<u16> = bytes.chunks_exact(2).map(|v| conv([v[0], v[1]])).collect();
+    decode_utf16(&units).wrap_err("invalid UTF-16 string")
+}
+
+async fn utf16_cstr<R>(src: &mut R, max: usize, conv: fn([u8; 2]) -> u16) -> Result<String>
+where
+    R: AsyncRead + Unpin + ?Sized,
+{
+    let mut units = Vec::new();
+    loop {
+        let mut raw = [0u8; 2];
+        src.read_exact(&mut raw).await?;
+        match conv(raw) {
+            0 => break,
+            _ if units.len() == max => {
+                return Err(eyre!("UTF-16 string overflow - no terminator within {max} units"))
+            },
+            v => units.push(v),
+        }
+    }
+    decode_utf16(&units).wrap_err("invalid UTF-16 string")
+}
+
+fn no_zero(val: &str) -> Result<()> {
+    match val.bytes().position(|v| v == 0) {
+        Some(pos) => Err(eyre!("string has embedded zero at offset 0x{pos:X}")),
+        None => Ok(()),
+    }
+}
+
+fn utf16_units(val: &str) -> Result<(u16, Vec<u16>)> {
+    let units: Vec<u16> = val.encode_utf16().collect();
+    let size = u16::try_from(units.len()).map_err(|_| {
+        eyre!(
+            "string of 0x{:X} UTF-16 units too long for u16 length prefix",
+            units.len()
+        )
+    })?;
+    Ok((size, units))
+}
+
+fn utf16_bytes(units: &[u16], conv: fn(u16) -> [u8; 2]) -> Vec<u8> {
+    units.iter().flat_map(|&v| conv(v)).collect()
+}
+
+impl<T> AsyncSafeRead for T where T: AsyncRead + Unpin + Send + ?Sized {}
+
+pub trait AsyncSafeRead: AsyncRead + Unpin + Send {
+    read_int!(get_u8, u8, from_be_bytes);
+    read_int!(get_i8, i8, from_be_bytes);
+    read_int!(get_u16be, u16, from_be_bytes);
+    read_int!(get_u16le, u16, from_le_bytes);
+    read_int!(get_i16be, i16, from_be_bytes);
+    read_int!(get_i16le, i16, from_le_bytes);
+    read_int!(get_u32be, u32, from_be_bytes);
+    read_int!(get_u32le, u32, from_le_bytes);
+    read_int!(get_i32be, i32, from_be_bytes);
+    read_int!(get_i32le, i32, from_le_bytes);
+    read_int!(get_u64be, u64, from_be_bytes);
+    read_int!(get_u64le, u64, from_le_bytes);
+    read_int!(get_i64be, i64, from_be_bytes);
+    read_int!(get_i64le, i64, from_le_bytes);
+    read_int!(get_u128be, u128, from_be_bytes);
+    read_int!(get_u128le, u128, from_le_bytes);
+    read_int!(get_i128be, i128, from_be_bytes);
+    read_int!(get_i128le, i128, from_le_bytes);
+
+    /// Return next byte without consuming it
+    fn peek_u8(&mut self) -> impl Future<Output = Result<u8>> + Send
+    where
+        Self: AsyncBufRead,
+    {
+        async move {
+            match self.fill_buf().await?.first() {
+                Some(&v) => Ok(v),
+                None => Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into()),
+            }
+        }
+    }
+
+    fn get_into(&mut self, buf: &mut [u8]) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            self.read_exact(buf).await?;
+            Ok(())
+        }
+    }
+
+    fn get_as_vec(&mut self, size: usize) -> impl Future<Output = Result<Vec<u8>>> + Send {
+        async move {
+            let mut buf = vec![0u8; size];
+            self.read_exact(&mut buf).await?;
+            Ok(buf)
+        }
+    }
+
+    fn get_until(&mut self, end: u8) -> impl Future<Output = Result<Vec<u8>>> + Send {
+        self.get_until_max(end, usize::MAX)
+    }
+
+    /// Read bytes up to `end` (consumed, but not returned), failing if there is more than
+    /// `max` bytes before it
+    fn get_until_max(
+        &mut self,
+        end: u8,
+        max: usize,
+    ) -> impl Future<Output = Result<Vec<u8>>> + Send {
+        async move {
+            let mut buf = Vec::new();
+            loop {
+                match self.get_u8().await? {
+                    v if v == end => return Ok(buf),
+                    _ if buf.len() == max => {
+                        return Err(eyre!(
+                            "get_until overflow - no terminator 0x{end:X} within {max} bytes"
+                        ))
+                    },
+                    v => buf.push(v),
+                }
+            }
+        }
+    }
+
+    fn get_asciiz(&mut self) -> impl Future<Output = Result<String>> + Send {
+        async move {
+            let bytes = self.get_until(0).await?;
+            Ok(String::from_utf8_lossy(&bytes).to_string())
+        }
+    }
+
+    /// Read zero-terminated string, failing on invalid UTF-8
+    fn get_cstr_utf8(&mut self) -> impl Future<Output = Result<String>> + Send {
+        self.get_cstr_max(usize::MAX)
+    }
+
+    /// Read zero-terminated string, at most `max` bytes long (sans terminator), failing on
+    /// invalid UTF-8
+    fn get_cstr_max(&mut self, max: usize) -> impl Future<Output = Result<String>> + Send {
+        async move { utf8(self.get_until_max(0, max).await?) }
+    }
+
+    /// Read string stored in fixed-width field of `size` bytes
+    fn get_fixed_str(
+        &mut self,
+        size: usize,
+        padding: Padding,
+    ) -> impl Future<Output = Result<String>> + Send {
+        async move {
+            let mut bytes = self.get_as_vec(size).await?;
+            let used = match padding {
+                Padding::Zero => bytes.iter().position(|&v| v == 0).unwrap_or(size),
+                Padding::Space => bytes.iter().rposition(|&v| v != b' ').map_or(0, |p| p + 1),
+            };
+            bytes.truncate(used);
+            utf8(bytes)
+        }
+    }
+
+    /// Read string prefixed with `u8` length
+    fn get_pstring_u8(&mut self) -> impl Future<Output = Result<String>> + Send {
+        async move {
+            let size = self.get_u8().await?;
+            sized_str(self, size as u128, usize::MAX).await
+        }
+    }
+
+    /// Read string prefixed with big-endian `u16` length
+    fn get_string_u16be(&mut self) -> impl Future<Output = Result<String>> + Send {
+        async move {
+            let size = self.get_u16be().await?;
+            sized_str(self, size as u128, usize::MAX).await
+        }
+    }
+
+    /// Read string prefixed with little-endian `u16` length
+    fn get_string_u16le(&mut self) -> impl Future<Output = Result<String>> + Send {
+        async move {
+            let size = self.get_u16le().await?;
+            sized_str(self, size as u128, usize::MAX).await
+        }
+    }
+
+    /// Read string prefixed with big-endian `u32` length
+    fn get_string_u32be(&mut self) -> impl Future<Output = Result<String>> + Send {
+        self.get_string_u32be_max(usize::MAX)
+    }
+
+    /// Read string prefixed with big-endian `u32` length, failing if it's longer than `max`
+    /// bytes
+    fn get_string_u32be_max(&mut self, max: usize) -> impl Future<Output = Result<String>> + Send {
+        async move {
+            let size = self.get_u32be().await?;
+            sized_str(self, size as u128, max).await
+        }
+    }
+
+    /// Read string prefixed with little-endian `u32` length
+    fn get_string_u32le(&mut self) -> impl Future<Output = Result<String>> + Send {
+        self.get_string_u32le_max(usize::MAX)
+    }
+
+    /// Read string prefixed with little-endian `u32` length, failing if it's longer than `max`
+    /// bytes
+    fn get_string_u32le_max(&mut self, max: usize) -> impl Future<Output = Result<String>> + Send {
+        async move {
+            let size = self.get_u32le().await?;
+            sized_str(self, size as u128, max).await
+        }
+    }
+
+    /// Read string prefixed with length encoded as in [`AsyncSafeRead::get_leb128`]
+    fn get_string_leb128(&mut self) -> impl Future<Output = Result<String>> + Send {
+        self.get_string_leb128_max(usize::MAX)
+    }
+
+    /// Read string prefixed with length encoded as in [`AsyncSafeRead::get_leb128`], failing
+    /// if it's longer than `max` bytes
+    fn get_string_leb128_max(
+        &mut self,
+        max: usize,
+    ) -> impl Future<Output = Result<String>> + Send {
+        async move {
+            let size = self.get_leb128().await?;
+            sized_str(self, size, max).await
+        }
+    }
+
+    /// Read `units` big-endian UTF-16 code units
+    fn get_utf16be(&mut self, units: usize) -> impl Future<Output = Result<String>> + Send {
+        async move { utf16(&sized(self, units as u64 * 2).await?, u16::from_be_bytes) }
+    }
+
+    /// Read big-endian UTF-16 string prefixed with `u16` count of code units
+    fn get_utf16be_prefixed(&mut self) -> impl Future<Output = Result<String>> + Send {
+        async move {
+            let units = self.get_u16be().await?;
+            self.get_utf16be(units as usize).await
+        }
+    }
+
+    /// Read zero-terminated big-endian UTF-16 string
+    fn get_utf16be_cstr(&mut self) -> impl Future<Output = Result<String>> + Send {
+        self.get_utf16be_cstr_max(usize::MAX)
+    }
+
+    /// Read zero-terminated big-endian UTF-16 string, at most `max` code units long (sans
+    /// terminator)
+    fn get_utf16be_cstr_max(&mut self, max: usize) -> impl Future<Output = Result<String>> + Send {
+        utf16_cstr(self, max, u16::from_be_bytes)
+    }
+
+    /// Read `units` little-endian UTF-16 code units
+    fn get_utf16le(&mut self, units: usize) -> impl Future<Output = Result<String>> + Send {
+        async move { utf16(&sized(self, units as u64 * 2).await?, u16::from_le_bytes) }
+    }
+
+    /// Read little-endian UTF-16 string prefixed with `u16` count of code units
+    fn get_utf16le_prefixed(&mut self) -> impl Future<Output = Result<String>> + Send {
+        async move {
+            let units = self.get_u16le().await?;
+            self.get_utf16le(units as usize).await
+        }
+    }
+
+    /// Read zero-terminated little-endian UTF-16 string
+    fn get_utf16le_cstr(&mut self) -> impl Future<Output = Result<String>> + Send {
+        self.get_utf16le_cstr_max(usize::MAX)
+    }
+
+    /// Read zero-terminated little-endian UTF-16 string, at most `max` code units long (sans
+    /// terminator)
+    fn get_utf16le_cstr_max(&mut self, max: usize) -> impl Future<Output = Result<String>> + Send {
+        utf16_cstr(self, max, u16::from_le_bytes)
+    }
+
+    /// Read `size` bytes of Modified UTF-8 (see [`crate::text::decode_mutf8`])
+    fn get_mutf8(&mut self, size: usize) -> impl Future<Output = Result<String>> + Send {
+        async move {
+            let bytes = sized(self, size as u64).await?;
+            decode_mutf8(&bytes).wrap_err("invalid Modified UTF-8 string")
+        }
+    }
+
+    /// Read `size` bytes of Latin-1
+    fn get_latin1(&mut self, size: usize) -> impl Future<Output = Result<String>> + Send {
+        async move { Ok(decode_latin1(&sized(self, size as u64).await?)) }
+    }
+
+    fn get_leb128(&mut self) -> impl Future<Output = Result<u128>> + Send {
+        async move {
+            let mut res = 0_u128;
+            let mut cnt = 0;
+
+            while cnt <= 128 {
+                let byte = self.get_u8().await?;
+
+                let last = (byte & 0x80) == 0;
+                let data = (byte & 0x7F) as u128;
+
+                res = res << 7 | data;
+
+                if last {
+                    return Ok(res);
+                }
+                cnt += 7;
+            }
+            Err(eyre!("get_leb128 overflow - no end byte after {cnt} bits"))
+        }
+    }
+
+    fn get_vlq128(&mut self) -> impl Future<Output = Result<u128>> + Send {
+        async move {
+            let mut res = 0_u128;
+            let mut cnt = 0;
+
+            while cnt <= 128 {
+                let byte = self.get_u8().await?;
+
+                let last = (byte & 0x80) == 0;
+                let data = (byte & 0x7F) as u128;
+
+                res |= data << cnt;
+
+                if last {
+                    return Ok(res);
+                }
+                cnt += 7;
+            }
+            Err(eyre!("get_vlq128 overflow - no end byte after {cnt} bits"))
+        }
+    }
+}
+
+impl<T> AsyncSafeWrite for T where T: AsyncWrite + Unpin + Send + ?Sized {}
+
+pub trait AsyncSafeWrite: AsyncWrite + Unpin + Send {
+    write_int!(put_u8, u8, to_be_bytes);
+    write_int!(put_i8, i8, to_be_bytes);
+    write_int!(put_u16_be, u16, to_be_bytes);
+    write_int!(put_u16_le, u16, to_le_bytes);
+    write_int!(put_i16_be, i16, to_be_bytes);
+    write_int!(put_i16_le, i16, to_le_bytes);
+    write_int!(put_u32_be, u32, to_be_bytes);
+    write_int!(put_u32_le, u32, to_le_bytes);
+    write_int!(put_i32_be, i32, to_be_bytes);
+    write_int!(put_i32_le, i32, to_le_bytes);
+    write_int!(put_u64_be, u64, to_be_bytes);
+    write_int!(put_u64_le, u64, to_le_bytes);
+    write_int!(put_i64_be, i64, to_be_bytes);
+    write_int!(put_i64_le, i64, to_le_bytes);
+    write_int!(put_u128_be, u128, to_be_bytes);
+    write_int!(put_u128_le, u128, to_le_bytes);
+    write_int!(put_i128_be, i128, to_be_bytes);
+    write_int!(put_i128_le, i128, to_le_bytes);
+
+    fn put_exact(&mut self, val: &[u8]) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            self.write_all(val).await?;
+            Ok(())
+        }
+    }
+
+    /// Write zero-terminated string, failing if it contains a zero byte
+    fn put_cstr(&mut self, val: &str) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            no_zero(val)?;
+            self.write_all(val.as_bytes()).await?;
+            self.put_u8(0).await
+        }
+    }
+
+    /// Write string into fixed-width field of `size` bytes, padding the rest
+    fn put_fixed_str(
+        &mut self,
+        val: &str,
+        size: usize,
+        padding: Padding,
+    ) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            if val.len() > size {
+                return Err(eyre!(
+                    "string of 0x{:X} bytes too long for 0x{size:X} byte field",
+                    val.len()
+                ));
+            }
+            let fill = match padding {
+                Padding::Zero => {
+                    no_zero(val)?;
+                    0
+                },
+                Padding::Space => b' ',
+            };
+            self.write_all(val.as_bytes()).await?;
+            self.write_all(&vec![fill; size - val.len()]).await?;
+            Ok(())
+        }
+    }
+
+    /// Write string prefixed with `u8` length
+    fn put_pstring_u8(&mut self, val: &str) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            let size =
+                u8::try_from(val.len()).map_err(|_| eyre!("string too long for u8 prefix"))?;
+            self.put_u8(size).await?;
+            self.put_exact(val.as_bytes()).await
+        }
+    }
+
+    /// Write string prefixed with big-endian `u16` length
+    fn put_string_u16_be(&mut self, val: &str) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            let size =
+                u16::try_from(val.len()).map_err(|_| eyre!("string too long for u16 prefix"))?;
+            self.put_u16_be(size).await?;
+            self.put_exact(val.as_bytes()).await
+        }
+    }
+
+    /// Write string prefixed with little-endian `u16` length
+    fn put_string_u16_le(&mut self, val: &str) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            let size =
+                u16::try_from(val.len()).map_err(|_| eyre!("string too long for u16 prefix"))?;
+            self.put_u16_le(size).await?;
+            self.put_exact(val.as_bytes()).await
+        }
+    }
+
+    /// Write string prefixed with big-endian `u32` length
+    fn put_string_u32_be(&mut self, val: &str) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            let size =
+                u32::try_from(val.len()).map_err(|_| eyre!("string too long for u32 prefix"))?;
+            self.put_u32_be(size).await?;
+            self.put_exact(val.as_bytes()).await
+        }
+    }
+
+    /// Write string prefixed with little-endian `u32` length
+    fn put_string_u32_le(&mut self, val: &str) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            let size =
+                u32::try_from(val.len()).map_err(|_| eyre!("string too long for u32 prefix"))?;
+            self.put_u32_le(size).await?;
+            self.put_exact(val.as_bytes()).await
+        }
+    }
+
+    /// Write string prefixed with length in format read by
+    /// [`AsyncSafeRead::get_string_leb128`]
+    fn put_string_leb128(&mut self, val: &str) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            self.put_leb128(val.len() as u128).await?;
+            self.put_exact(val.as_bytes()).await
+        }
+    }
+
+    /// Write string as big-endian UTF-16, without length or terminator
+    fn put_utf16_be(&mut self, val: &str) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            let units: Vec<u16> = val.encode_utf16().collect();
+            self.put_exact(&utf16_bytes(&units, u16::to_be_bytes)).await
+        }
+    }
+
+    /// Write string as big-endian UTF-16, prefixed with `u16` count of code units
+    fn put_utf16_be_prefixed(&mut self, val: &str) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            let (size, units) = utf16_units(val)?;
+            self.put_u16_be(size).await?;
+            self.put_exact(&utf16_bytes(&units, u16::to_be_bytes)).await
+        }
+    }
+
+    /// Write string as zero-terminated big-endian UTF-16, failing if it contains a zero
+    fn put_utf16_be_cstr(&mut self, val: &str) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            no_zero(val)?;
+            self.put_utf16_be(val).await?;
+            self.put_u16_be(0).await
+        }
+    }
+
+    /// Write string as little-endian UTF-16, without length or terminator
+    fn put_utf16_le(&mut self, val: &str) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            let units: Vec<u16> = val.encode_utf16().collect();
+            self.put_exact(&utf16_bytes(&units, u16::to_le_bytes)).await
+        }
+    }
+
+    /// Write string as little-endian UTF-16, prefixed with `u16` count of code units
+    fn put_utf16_le_prefixed(&mut self, val: &str) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            let (size, units) = utf16_units(val)?;
+            self.put_u16_le(size).await?;
+            self.put_exact(&utf16_bytes(&units, u16::to_le_bytes)).await
+        }
+    }
+
+    /// Write string as zero-terminated little-endian UTF-16, failing if it contains a zero
+    fn put_utf16_le_cstr(&mut self, val: &str) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            no_zero(val)?;
+            self.put_utf16_le(val).await?;
+            self.put_u16_le(0).await
+        }
+    }
+
+    /// Write string as Modified UTF-8 (see [`crate::text::encode_mutf8`]), without length
+    fn put_mutf8(&mut self, val: &str) -> impl Future<Output = Result<()>> + Send {
+        async move { self.put_exact(&encode_mutf8(val)).await }
+    }
+
+    /// Write string as Latin-1, failing on characters above U+00FF
+    fn put_latin1(&mut self, val: &str) -> impl Future<Output = Result<()>> + Send {
+        async move { self.put_exact(&encode_latin1(val)?).await }
+    }
+
+    /// Write value in format read by [`AsyncSafeRead::get_leb128`]
+    fn put_leb128(&mut self, val: u128) -> impl Future<Output = Result<()>> + Send {
+        async move {
+            let mut buf = [0u8; 19];
+            let mut pos = buf.len() - 1;
+            let mut rest = val;
+
+            buf[pos] = (rest & 0x7F) as u8;
+            rest >>= 7;
+            while rest != 0 {
+                pos -= 1;
+                buf[pos] = (rest & 0x7F) as u8 | 0x80;
+                rest >>= 7;
+            }
+            self.put_exact(&buf[pos ..]).await
+        }
+    }
+}
+
+macro_rules! auto_module {
+    ($module:ident, $($typ:ty => $get:ident, $put:ident;)+) => {
+        pub mod $module {
+            use std::future::Future;
+
+            use eyre::Result;
+
+            use super::{AsyncSafeRead, AsyncSafeWrite};
+
+            /// Type with known size and byte order
+            pub trait AsyncAuto: Sized + Send {
+                fn read_from<SR>(src: &mut SR) -> impl Future<Output = Result<Self>> + Send
+                where
+                    SR: AsyncSafeRead + ?Sized;
+
+                fn write_to<SW>(self, dst: &mut SW) -> impl Future<Output = Result<()>> + Send
+                where
+                    SW: AsyncSafeWrite + ?Sized;
+            }
+
+            pub trait AsyncSafeReader: AsyncSafeRead {
+                fn get_auto<T: AsyncAuto>(&mut self) -> impl Future<Output = Result<T>> + Send {
+                    T::read_from(self)
+                }
+            }
+
+            pub trait AsyncSafeWriter: AsyncSafeWrite {
+                fn put_auto<T: AsyncAuto>(
+                    &mut self,
+                    val: T,
+                ) -> impl Future<Output = Result<()>> + Send {
+                    val.write_to(self)
+                }
+            }
+
+            impl<SR> AsyncSafeReader for SR where SR: AsyncSafeRead + ?Sized {}
+
+            impl<SW> AsyncSafeWriter for SW where SW: AsyncSafeWrite + ?Sized {}
+
+            $(
+                impl AsyncAuto for $typ {
+                    fn read_from<SR>(src: &mut SR) -> impl Future<Output = Result<Self>> + Send
+                    where
+                        SR: AsyncSafeRead + ?Sized,
+                    {
+                        src.$get()
+                    }
+
+                    fn write_to<SW>(self, dst: &mut SW) -> impl Future<Output = Result<()>> + Send
+                    where
+                        SW: AsyncSafeWrite + ?Sized,
+                    {
+                        dst.$put(self)
+                    }
+                }
+            )+
+        }
+    };
+}
+
+auto_module!(be,
+    u8 => get_u8, put_u8;
+    i8 => get_i8, put_i8;
+    u16 => get_u16be, put_u16_be;
+    i16 => get_i16be, put_i16_be;
+    u32 => get_u32be, put_u32_be;
+    i32 => get_i32be, put_i32_be;
+    u64 => get_u64be, put_u64_be;
+    i64 => get_i64be, put_i64_be;
+    u128 => get_u128be, put_u128_be;
+    i128 => get_i128be, put_i128_be;
+);
+
+auto_module!(le,
+    u8 => get_u8, put_u8;
+    i8 => get_i8, put_i8;
+    u16 => get_u16le, put_u16_le;
+    i16 => get_i16le, put_i16_le;
+    u32 => get_u32le, put_u32_le;
+    i32 => get_i32le, put_i32_le;
+    u64 => get_u64le, put_u64_le;
+    i64 => get_i64le, put_i64_le;
+    u128 => get_u128le, put_u128_le;
+    i128 => get_i128le, put_i128_le;
+);
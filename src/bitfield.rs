@@ -0,0 +1,117 @@
+//! Named fields packed in an unsigned integer
+//!
+//! [`bitfield!`](crate::bitfield!) declares a newtype over `u8` .. `u128` with a getter and a
+//! validating setter per field. Fields are given as half-open bit ranges, bit 0 being least
+//! significant. The whole word is read and written with `read_auto`/`write_auto` of
+//! [`crate::be`], [`crate::le`] or [`crate::ne`].
+//!
+//! ```
+//! use pakr_typed_io::bitfield;
+//! use pakr_typed_io::be::*;
+//!
+//! use std::io::Cursor;
+//!
+//! bitfield! {
+//!     /// IPv4 flags and fragment offset
+//!     pub struct Fragment(u16) {
+//!         offset, set_offset: 0 .. 13;
+//!         more, set_more: 13 .. 14;
+//!         dont, set_dont: 14 .. 15;
+//!     }
+//! }
+//!
+//! fn main() -> Result<(), eyre::Report> {
+//!     let mut cur = Cursor::new(&[0x40_u8, 0x10][..]);
+//!     let mut frag: Fragment = cur.read_auto()?;
+//!
+//!     assert_eq!(1, frag.dont());
+//!     assert_eq!(0, frag.more());
+//!     assert_eq!(0x10, frag.offset());
+//!
+//!     frag.set_more(1)?;
+//!     frag.set_offset(0x1FFF)?;
+//!     assert_eq!(0x7FFF, frag.0);
+//!
+//!     // Values not fitting the field are rejected
+//!     assert_eq!(
+//!         frag.set_dont(2).unwrap_err().to_string(),
+//!         "value dont=0x2 not in expected range 0..=1"
+//!     );
+//!
+//!     let mut buf = vec![];
+//!     Cursor::new(&mut buf).write_auto(frag)?;
+//!     assert_eq!(vec![0x7F, 0xFF], buf);
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! Bit ranges are checked at compile time.
+//!
+//! ```compile_fail
+//! pakr_typed_io::bitfield! {
+//!     struct Bad(u8) {
+//!         empty, set_empty: 3 .. 3;
+//!     }
+//! }
+//! ```
+
+/// Type convertible to and from the integer it's packed in
+pub trait Bitfield: Copy {
+    type Raw;
+
+    fn from_raw(raw: Self::Raw) -> Self;
+
+    fn to_raw(self) -> Self::Raw;
+}
+
+/// Declare a [`Bitfield`] newtype with named fields, see [module docs](mod@crate::bitfield)
+#[macro_export]
+macro_rules! bitfield {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident($raw:ty) {
+            $($field:ident, $setter:ident : $lo:literal .. $hi:literal;)*
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+        $vis struct $name(pub $raw);
+
+        // Empty or out of word ranges would overflow shifts at runtime
+        const _: () = {
+            $(
+                assert!(
+                    $lo < $hi && $hi <= <$raw>::BITS,
+                    concat!("invalid bit range of field `", stringify!($field), "`")
+                );
+            )*
+        };
+
+        impl $name {
+            $(
+                pub fn $field(&self) -> $raw {
+                    let max = <$raw>::MAX >> (<$raw>::BITS - ($hi - $lo));
+                    (self.0 >> $lo) & max
+                }
+
+                pub fn $setter(&mut self, val: $raw) -> $crate::eyre::Result<()> {
+                    use $crate::validator::Validator;
+
+                    let max = <$raw>::MAX >> (<$raw>::BITS - ($hi - $lo));
+                    let val = val.validate_in_range_ctx(0 ..= max, stringify!($field))?;
+                    self.0 = self.0 & !(max << $lo) | val << $lo;
+                    Ok(())
+                }
+            )*
+        }
+
+        impl $crate::bitfield::Bitfield for $name {
+            type Raw = $raw;
+
+            fn from_raw(raw: $raw) -> Self { Self(raw) }
+
+            fn to_raw(self) -> $raw { self.0 }
+        }
+    };
+}
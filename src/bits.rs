@@ -0,0 +1,422 @@
+//! Bit-granular reading and writing
+//!
+//! ```
+//! use pakr_typed_io::bits::{BitOrder, BitReader};
+//!
+//! use std::io::Cursor;
+//!
+//! fn main() -> Result<(), eyre::Report> {
+//!     let buf = vec![0b1011_0101, 0b1000_0000, 0x42];
+//!     let mut rd = BitReader::new(Cursor::new(&buf), BitOrder::MsbFirst)?;
+//!
+//!     assert!(rd.read_bit()?);
+//!     assert_eq!(0b01, rd.read_bits(2)?);
+//!     assert_eq!(0, rd.read_ue()?); // `1`
+//!     assert_eq!(1, rd.read_se()?); // `010`
+//!     assert_eq!(-2, rd.read_signed(3)?); // `110`
+//!     assert_eq!(10, rd.bit_position());
+//!
+//!     rd.byte_align();
+//!     let mut cur = rd.into_inner()?;
+//!     assert_eq!(0x42, pakr_typed_io::SafeRead::read_u8(&mut cur)?);
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! ```
+//! use pakr_typed_io::bits::{BitOrder, BitWriter};
+//!
+//! use std::io::Cursor;
+//!
+//! fn main() -> Result<(), eyre::Report> {
+//!     let mut buf = vec![];
+//!     {
+//!         let mut wr = BitWriter::new(Cursor::new(&mut buf), BitOrder::MsbFirst)?;
+//!         wr.write_bit(true)?;
+//!         wr.write_bits(0b01, 2)?;
+//!         wr.write_ue(0)?;
+//!         wr.write_se(1)?;
+//!         wr.write_signed(-2, 3)?;
+//!
+//!         // Still in the middle of second byte, `finish` would fail
+//!         assert_eq!(10, wr.bit_position());
+//!         assert!(!wr.is_aligned());
+//!
+//!         wr.set_pad(true);
+//!         wr.align()?;
+//!         wr.finish()?;
+//!     }
+//!     assert_eq!(vec![0b1011_0101, 0b1011_1111], buf);
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! With [`BitOrder::LsbFirst`] bits are taken from least significant end of each byte, so
+//! fields spanning bytes continue in the next byte's low bits. Exp-Golomb codes still start
+//! with their most significant bit, as DEFLATE Huffman codes do.
+//!
+//! ```
+//! use pakr_typed_io::bits::{BitOrder, BitReader};
+//!
+//! use std::io::Cursor;
+//!
+//! fn main() -> Result<(), eyre::Report> {
+//!     // DEFLATE stored block: BFINAL, BTYPE, padding, then LEN and NLEN
+//!     let buf = b"\x01\x05\x00\xfa\xffhello\x34\x12\x04";
+//!     let mut rd = BitReader::new(Cursor::new(&buf[..]), BitOrder::LsbFirst)?;
+//!
+//!     assert!(rd.read_bit()?);
+//!     assert_eq!(0b00, rd.read_bits(2)?);
+//!     rd.byte_align();
+//!     assert_eq!(0x0005, rd.read_bits(16)?);
+//!     assert_eq!(0xFFFA, rd.read_bits(16)?);
+//!
+//!     let mut cur = rd.into_inner()?;
+//!     assert_eq!("hello", pakr_typed_io::SafeRead::read_latin1(&mut cur, 5)?);
+//!
+//!     let mut rd = BitReader::new(cur, BitOrder::LsbFirst)?;
+//!     assert_eq!(0x234, rd.read_bits(12)?);
+//!     assert_eq!(0x1, rd.read_bits(4)?);
+//!     assert_eq!(3, rd.read_ue()?); // `00100` from lowest bit up
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! ```
+//! use pakr_typed_io::bits::{BitOrder, BitWriter};
+//!
+//! use std::io::Cursor;
+//!
+//! fn main() -> Result<(), eyre::Report> {
+//!     let mut buf = vec![];
+//!     {
+//!         let mut wr = BitWriter::new(Cursor::new(&mut buf), BitOrder::LsbFirst)?;
+//!         // Empty DEFLATE block with fixed codes: BFINAL, BTYPE, end-of-block code
+//!         wr.write_bit(true)?;
+//!         wr.write_bits(0b01, 2)?;
+//!         wr.write_bits(0, 7)?;
+//!         wr.align()?;
+//!
+//!         wr.write_bits(0x234, 12)?;
+//!         wr.write_bits(0x1, 4)?;
+//!         wr.write_ue(3)?;
+//!         wr.align()?;
+//!         wr.finish()?;
+//!     }
+//!     assert_eq!(vec![0x03, 0x00, 0x34, 0x12, 0b0000_0100], buf);
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use eyre::{eyre, Result};
+
+use crate::{SafeRead, SafeSeek, SafeWrite};
+
+/// Order in which bits are taken from each byte
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BitOrder {
+    /// Most significant bit first, first bit read is most significant in result (H.264,
+    /// MPEG-TS)
+    MsbFirst,
+    /// Least significant bit first, first bit read is least significant in result (DEFLATE)
+    LsbFirst,
+}
+
+/// Reader of individual bits from a byte stream
+pub struct BitReader<R> {
+    inner: R,
+    order: BitOrder,
+    /// Byte being consumed, with already consumed bits shifted out (`LsbFirst`) or ignored
+    /// (`MsbFirst`)
+    cache: u8,
+    /// Bits of `cache` not consumed yet
+    left:  u32,
+    /// Bit position in stream
+    pos:   u64,
+}
+
+impl<R> BitReader<R>
+where
+    R: SafeRead,
+{
+    pub fn new(mut inner: R, order: BitOrder) -> Result<Self> {
+        let pos = inner.offset().unwrap_or(0) * 8;
+        Ok(Self {
+            inner,
+            order,
+            cache: 0,
+            left: 0,
+            pos,
+        })
+    }
+
+    pub fn order(&self) -> BitOrder { self.order }
+
+    /// Position in underlying stream, in bits
+    pub fn bit_position(&self) -> u64 { self.pos }
+
+    pub fn is_aligned(&self) -> bool { self.left == 0 }
+
+    /// Skip remaining bits of current byte
+    pub fn byte_align(&mut self) {
+        self.pos += self.left as u64;
+        self.left = 0;
+    }
+
+    /// Return underlying reader, positioned at first byte not touched by bit reads
+    pub fn into_inner(self) -> Result<R> {
+        if self.is_aligned() {
+            Ok(self.inner)
+        } else {
+            Err(eyre!(
+                "bit reader not aligned, {} bits left at bit 0x{:X}",
+                self.left,
+                self.pos
+            ))
+        }
+    }
+
+    pub fn read_bit(&mut self) -> Result<bool> { Ok(self.read_bits(1)? != 0) }
+
+    /// Read `count` bits (up to 64) as unsigned number
+    pub fn read_bits(&mut self, count: u32) -> Result<u64> {
+        if count > 64 {
+            return Err(eyre!("can't read {count} bits at once, 64 is maximum"));
+        }
+
+        let mut res = 0_u64;
+        let mut got = 0;
+        while got < count {
+            if self.left == 0 {
+                self.cache = self.inner.read_u8()?;
+                self.left = 8;
+            }
+
+            let take = (count - got).min(self.left);
+            let mask = (1_u64 << take) - 1;
+            match self.order {
+                BitOrder::MsbFirst => {
+                    let chunk = (self.cache as u64 >> (self.left - take)) & mask;
+                    res = res << take | chunk;
+                },
+                BitOrder::LsbFirst => {
+                    let chunk = self.cache as u64 & mask;
+                    self.cache = (self.cache as u64 >> take) as u8;
+                    res |= chunk << got;
+                },
+            }
+            self.left -= take;
+            self.pos += take as u64;
+            got += take;
+        }
+        Ok(res)
+    }
+
+    /// Read `count` bits (up to 64) as two's complement signed number
+    pub fn read_signed(&mut self, count: u32) -> Result<i64> {
+        let raw = self.read_bits(count)?;
+        if count == 0 || count == 64 {
+            return Ok(raw as i64);
+        }
+        let shift = 64 - count;
+        Ok(((raw << shift) as i64) >> shift)
+    }
+
+    /// Read unsigned Exp-Golomb code. Code bits are always most significant first.
+    pub fn read_ue(&mut self) -> Result<u64> {
+        let start = self.pos;
+        let mut zeros = 0;
+        while !self.read_bit()? {
+            zeros += 1;
+            if zeros > 63 {
+                return Err(eyre!("Exp-Golomb code at bit 0x{start:X} too long"));
+            }
+        }
+        Ok((1_u64 << zeros) - 1 + self.read_code_bits(zeros)?)
+    }
+
+    /// Read `count` bits of a code defined as bit sequence, most significant first, regardless
+    /// of bit order of the stream
+    fn read_code_bits(&mut self, count: u32) -> Result<u64> {
+        match self.order {
+            BitOrder::MsbFirst => self.read_bits(count),
+            BitOrder::LsbFirst => {
+                let mut res = 0_u64;
+                for _ in 0 .. count {
+                    res = res << 1 | self.read_bit()? as u64;
+                }
+                Ok(res)
+            },
+        }
+    }
+
+    /// Read signed Exp-Golomb code
+    pub fn read_se(&mut self) -> Result<i64> {
+        let code = self.read_ue()?;
+        if code & 1 == 1 {
+            Ok((code / 2 + 1) as i64)
+        } else {
+            Ok(-((code / 2) as i64))
+        }
+    }
+}
+
+/// Writer of individual bits into a byte stream
+pub struct BitWriter<W> {
+    inner: W,
+    order: BitOrder,
+    /// Byte being filled
+    cache: u8,
+    /// Bits of `cache` already filled
+    used:  u32,
+    /// Value of bits used to pad incomplete byte
+    pad:   bool,
+    /// Bit position in stream
+    pos:   u64,
+}
+
+impl<W> BitWriter<W>
+where
+    W: SafeWrite,
+{
+    pub fn new(mut inner: W, order: BitOrder) -> Result<Self> {
+        let pos = inner.tell()? * 8;
+        Ok(Self {
+            inner,
+            order,
+            cache: 0,
+            used: 0,
+            pad: false,
+            pos,
+        })
+    }
+
+    pub fn order(&self) -> BitOrder { self.order }
+
+    /// Select value of bits used by [`BitWriter::align`] to fill incomplete byte
+    pub fn set_pad(&mut self, pad: bool) { self.pad = pad; }
+
+    /// Position in underlying stream, in bits
+    pub fn bit_position(&self) -> u64 { self.pos }
+
+    pub fn is_aligned(&self) -> bool { self.used == 0 }
+
+    /// Fill incomplete byte with padding bits and write it out
+    pub fn align(&mut self) -> Result<()> {
+        if self.used != 0 {
+            let fill = if self.pad { u64::MAX } else { 0 };
+            self.write_bits(fill >> (64 - (8 - self.used)), 8 - self.used)?;
+        }
+        Ok(())
+    }
+
+    /// Align to byte boundary and flush underlying writer
+    pub fn flush(&mut self) -> Result<()> {
+        self.align()?;
+        self.inner.flush()?;
+        Ok(())
+    }
+
+    /// Return underlying writer, failing if there are bits not written out yet
+    pub fn finish(self) -> Result<W> {
+        if self.is_aligned() {
+            Ok(self.inner)
+        } else {
+            Err(eyre!(
+                "bit writer not aligned, {} bits pending at bit 0x{:X}",
+                self.used,
+                self.pos
+            ))
+        }
+    }
+
+    pub fn write_bit(&mut self, val: bool) -> Result<()> { self.write_bits(val as u64, 1) }
+
+    /// Write `count` (up to 64) lowest bits of `val`, failing if it doesn't fit
+    pub fn write_bits(&mut self, val: u64, count: u32) -> Result<()> {
+        if count > 64 {
+            return Err(eyre!("can't write {count} bits at once, 64 is maximum"));
+        }
+        if count < 64 && val >> count != 0 {
+            return Err(eyre!("value 0x{val:X} doesn't fit in {count} bits"));
+        }
+
+        let mut done = 0;
+        while done < count {
+            let take = (count - done).min(8 - self.used);
+            let mask = (1_u64 << take) - 1;
+            match self.order {
+                BitOrder::MsbFirst => {
+                    let chunk = (val >> (count - done - take)) & mask;
+                    self.cache |= (chunk << (8 - self.used - take)) as u8;
+                },
+                BitOrder::LsbFirst => {
+                    let chunk = (val >> done) & mask;
+                    self.cache |= (chunk << self.used) as u8;
+                },
+            }
+            self.used += take;
+            self.pos += take as u64;
+            done += take;
+
+            if self.used == 8 {
+                self.inner.write_u8(self.cache)?;
+                self.cache = 0;
+                self.used = 0;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write `count` (up to 64) bits of two's complement `val`, failing if it doesn't fit
+    pub fn write_signed(&mut self, val: i64, count: u32) -> Result<()> {
+        if count == 0 || count > 64 {
+            return Err(eyre!("can't write signed value in {count} bits"));
+        }
+        let shift = 64 - count;
+        if (val << shift) >> shift != val {
+            return Err(eyre!("value {val} doesn't fit in {count} bits"));
+        }
+        self.write_bits(val as u64 & (u64::MAX >> shift), count)
+    }
+
+    /// Write unsigned Exp-Golomb code. Code bits are always most significant first.
+    pub fn write_ue(&mut self, val: u64) -> Result<()> {
+        let code = val
+            .checked_add(1)
+            .ok_or_else(|| eyre!("value 0x{val:X} too big for Exp-Golomb code"))?;
+        let size = 64 - code.leading_zeros();
+        self.write_bits(0, size - 1)?;
+        self.write_code_bits(code, size)
+    }
+
+    /// Write `count` bits of a code defined as bit sequence, most significant first, regardless
+    /// of bit order of the stream
+    fn write_code_bits(&mut self, val: u64, count: u32) -> Result<()> {
+        match self.order {
+            BitOrder::MsbFirst => self.write_bits(val, count),
+            BitOrder::LsbFirst => {
+                for idx in (0 .. count).rev() {
+                    self.write_bit(val >> idx & 1 == 1)?;
+                }
+                Ok(())
+            },
+        }
+    }
+
+    /// Write signed Exp-Golomb code
+    pub fn write_se(&mut self, val: i64) -> Result<()> {
+        let code = if val > 0 {
+            (val as u64) * 2 - 1
+        } else {
+            val.unsigned_abs()
+                .checked_mul(2)
+                .ok_or_else(|| eyre!("value {val} too small for Exp-Golomb code"))?
+        };
+        self.write_ue(code)
+    }
+}
@@ -0,0 +1,500 @@
+//! Running checksums over read or written data
+//!
+//! [`ChecksumReader`] and [`ChecksumWriter`] wrap a stream and feed every byte passing through
+//! to a [`Checksum`]. Each byte of the stream is counted at most once, in order it was first
+//! touched, so seeking back and reading again doesn't disturb the running value. Terminator
+//! scans like [`SafeRead::read_until`] go byte by byte through [`ChecksumReader`], so nothing
+//! past the terminator gets counted early.
+//!
+//! A counted byte can't be changed later, so back-patching with [`SafeWrite::fill`] inside
+//! the range covered by [`ChecksumWriter`] is an error. Fields before it, like a length not
+//! covered by checksum, can be patched freely.
+//!
+//! ```
+//! use pakr_typed_io::*;
+//! use pakr_typed_io::checksum::{ChecksumReader, ChecksumWriter, Crc32};
+//! use pakr_typed_io::endian::Endian;
+//!
+//! use std::io::Cursor;
+//!
+//! fn main() -> Result<(), eyre::Report> {
+//!     let mut buf = vec![];
+//!     {
+//!         let mut wr = ChecksumWriter::new(Cursor::new(&mut buf), Crc32::default())?;
+//!         wr.write_exact(b"IEND")?;
+//!         wr.write_checksum(Endian::Big)?;
+//!     }
+//!     assert_eq!(b"IEND\xae\x42\x60\x82", &buf[..]);
+//!
+//!     let mut rd = ChecksumReader::new(Cursor::new(&buf), Crc32::default())?;
+//!     assert_eq!(b"IEND", &rd.read_as_vec(4)?[..]);
+//!     assert_eq!(0xAE42_6082, rd.verify_checksum(Endian::Big)?);
+//!     assert_eq!(0 .. 4, rd.covered());
+//!
+//!     // Mismatch is an error
+//!     let mut rd = ChecksumReader::new(Cursor::new(&buf), Crc32::default())?;
+//!     assert_eq!(b"IE", &rd.read_as_vec(2)?[..]);
+//!     rd.reset();
+//!     rd.read_as_vec(2)?;
+//!     assert_eq!(
+//!         rd.verify_checksum(Endian::Big).unwrap_err().to_string(),
+//!         "checksum 0x5E92F5FD doesn't match stored 0xAE426082 at 0x4"
+//!     );
+//!
+//!     // Checksum right after a terminated string
+//!     let mut buf = vec![];
+//!     {
+//!         let mut wr = ChecksumWriter::new(Cursor::new(&mut buf), Crc32::default())?;
+//!         wr.write_cstr("abc")?;
+//!         wr.write_checksum(Endian::Big)?;
+//!     }
+//!     let mut rd = ChecksumReader::new(Cursor::new(&buf), Crc32::default())?;
+//!     assert_eq!("abc", rd.read_cstr_utf8()?);
+//!     rd.verify_checksum(Endian::Big)?;
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! ```
+//! use pakr_typed_io::*;
+//! use pakr_typed_io::checksum::{ChecksumWriter, Crc32};
+//! use pakr_typed_io::endian::Endian;
+//!
+//! use std::io::Cursor;
+//!
+//! fn main() -> Result<(), eyre::Report> {
+//!     // PNG chunk: length isn't covered by CRC, so it can be filled in afterwards
+//!     let mut buf = vec![];
+//!     {
+//!         let mut wr = ChecksumWriter::new(Cursor::new(&mut buf), Crc32::default())?;
+//!         let size = wr.reserve_u32_be()?;
+//!         wr.reset();
+//!         wr.write_exact(b"tEXtkey\0val")?;
+//!         wr.fill(size, 7)?;
+//!         wr.write_checksum(Endian::Big)?;
+//!     }
+//!     assert_eq!(b"\0\0\0\x07tEXt", &buf[.. 8]);
+//!
+//!     // Length covered by checksum can't be patched
+//!     let mut wr = ChecksumWriter::new(Cursor::new(vec![]), Crc32::default())?;
+//!     let size = wr.reserve_u32_be()?;
+//!     wr.write_exact(b"data")?;
+//!     assert_eq!(
+//!         wr.fill(size, 4).unwrap_err().to_string(),
+//!         "can't overwrite data at 0x0 already counted in checksum"
+//!     );
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use std::{
+    fmt,
+    io::{ErrorKind, Read, Seek, SeekFrom, Write},
+};
+
+use eyre::{eyre, Result};
+
+use crate::{be, endian::Endian, le, SafeRead, SafeSeek, SafeWrite};
+
+/// Running checksum algorithm
+pub trait Checksum {
+    type Output: Clone + PartialEq + fmt::Debug;
+
+    fn update(&mut self, data: &[u8]);
+
+    /// Start over, as if no data was seen
+    fn reset(&mut self);
+
+    /// Value over all data seen so far
+    fn finalize(&self) -> Self::Output;
+}
+
+const fn crc32_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut idx = 0;
+    while idx < 256 {
+        let mut crc = idx as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { crc >> 1 ^ 0xEDB8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[idx] = crc;
+        idx += 1;
+    }
+    table
+}
+
+const fn crc16_table() -> [u16; 256] {
+    let mut table = [0u16; 256];
+    let mut idx = 0;
+    while idx < 256 {
+        let mut crc = idx as u16;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { crc >> 1 ^ 0xA001 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[idx] = crc;
+        idx += 1;
+    }
+    table
+}
+
+static CRC32_TABLE: [u32; 256] = crc32_table();
+
+static CRC16_TABLE: [u16; 256] = crc16_table();
+
+/// CRC-32 as used by PNG, zlib and Ethernet
+#[derive(Clone, Debug)]
+pub struct Crc32 {
+    crc: u32,
+}
+
+impl Default for Crc32 {
+    fn default() -> Self { Self { crc: 0xFFFF_FFFF } }
+}
+
+impl Checksum for Crc32 {
+    type Output = u32;
+
+    fn update(&mut self, data: &[u8]) {
+        for &v in data {
+            self.crc = CRC32_TABLE[((self.crc ^ v as u32) & 0xFF) as usize] ^ self.crc >> 8;
+        }
+    }
+
+    fn reset(&mut self) { *self = Self::default(); }
+
+    fn finalize(&self) -> u32 { !self.crc }
+}
+
+/// CRC-16/ARC, as used by LHA and many serial protocols
+///
+/// ```
+/// use pakr_typed_io::checksum::{Checksum, Crc16};
+///
+/// let mut crc = Crc16::default();
+/// crc.update(b"123456789");
+/// assert_eq!(0xBB3D, crc.finalize());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Crc16 {
+    crc: u16,
+}
+
+impl Checksum for Crc16 {
+    type Output = u16;
+
+    fn update(&mut self, data: &[u8]) {
+        for &v in data {
+            self.crc = CRC16_TABLE[((self.crc ^ v as u16) & 0xFF) as usize] ^ self.crc >> 8;
+        }
+    }
+
+    fn reset(&mut self) { *self = Self::default(); }
+
+    fn finalize(&self) -> u16 { self.crc }
+}
+
+/// Adler-32, as used by zlib
+///
+/// ```
+/// use pakr_typed_io::checksum::{Adler32, Checksum};
+///
+/// let mut sum = Adler32::default();
+/// sum.update(b"Wiki");
+/// sum.update(b"pedia");
+/// assert_eq!(0x11E6_0398, sum.finalize());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Adler32 {
+    a: u32,
+    b: u32,
+}
+
+impl Default for Adler32 {
+    fn default() -> Self { Self { a: 1, b: 0 } }
+}
+
+impl Checksum for Adler32 {
+    type Output = u32;
+
+    fn update(&mut self, data: &[u8]) {
+        const MOD: u32 = 65521;
+        // Largest block that can't overflow `b` before reduction
+        for block in data.chunks(5552) {
+            for &v in block {
+                self.a += v as u32;
+                self.b += self.a;
+            }
+            self.a %= MOD;
+            self.b %= MOD;
+        }
+    }
+
+    fn reset(&mut self) { *self = Self::default(); }
+
+    fn finalize(&self) -> u32 { self.b << 16 | self.a }
+}
+
+/// Reader feeding all read bytes to a checksum
+pub struct ChecksumReader<R, C> {
+    inner:  R,
+    csum:   C,
+    /// Position where checksum starts
+    start:  u64,
+    /// Current position in stream
+    pos:    u64,
+    /// Position up to which data was seen, whether fed to checksum or not
+    done:   u64,
+    /// Position just past last byte fed to checksum
+    hashed: u64,
+    /// Don't feed data to checksum
+    paused: bool,
+}
+
+impl<R, C> ChecksumReader<R, C>
+where
+    R: SafeRead,
+    C: Checksum,
+{
+    pub fn new(mut inner: R, csum: C) -> Result<Self> {
+        let pos = inner.offset().unwrap_or(0);
+        Ok(Self {
+            inner,
+            csum,
+            start: pos,
+            pos,
+            done: pos,
+            hashed: pos,
+            paused: false,
+        })
+    }
+
+    pub fn get_ref(&self) -> &R { &self.inner }
+
+    pub fn into_inner(self) -> R { self.inner }
+
+    /// Restart checksum at current position
+    pub fn reset(&mut self) {
+        self.csum.reset();
+        self.start = self.pos;
+        self.done = self.pos;
+        self.hashed = self.pos;
+    }
+
+    /// Range of stream positions covered by checksum so far, ending at last byte fed to it.
+    /// A stored checksum passed through in between without [`Self::reset`] is inside the
+    /// range, but isn't counted.
+    pub fn covered(&self) -> std::ops::Range<u64> { self.start .. self.hashed }
+
+    /// Run `body` with checksum not counting data passing through
+    pub(crate) fn unhashed<T, F>(&mut self, body: F) -> Result<T>
+    where
+        F: FnOnce(&mut Self) -> Result<T>,
+    {
+        self.paused = true;
+        let res = body(self);
+        self.paused = false;
+        res
+    }
+
+    /// Checksum of data read so far
+    pub fn finalize(&self) -> C::Output { self.csum.finalize() }
+
+    /// Read stored checksum, not counting it, and compare with running value
+    pub fn verify_checksum(&mut self, endian: Endian) -> Result<C::Output>
+    where
+        Self: be::SafeReader<C::Output> + le::SafeReader<C::Output>,
+        C::Output: fmt::UpperHex,
+    {
+        let expected = self.finalize();
+        let at = self.pos;
+
+        let stored = self.unhashed(|rd| match endian {
+            Endian::Big => be::SafeReader::<C::Output>::read_auto(rd),
+            Endian::Little => le::SafeReader::<C::Output>::read_auto(rd),
+        })?;
+
+        if stored == expected {
+            Ok(stored)
+        } else {
+            Err(eyre!(
+                "checksum 0x{:X} doesn't match stored 0x{:X} at 0x{at:X}",
+                expected,
+                stored
+            ))
+        }
+    }
+}
+
+impl<R, C> Read for ChecksumReader<R, C>
+where
+    R: SafeRead,
+    C: Checksum,
+{
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let got = self.inner.read(buf)?;
+        let end = self.pos + got as u64;
+        if self.paused {
+            self.done = self.done.max(end);
+        } else if end > self.done {
+            let skip = self.done.saturating_sub(self.pos) as usize;
+            self.csum.update(&buf[skip .. got]);
+            self.done = end;
+            self.hashed = end;
+        }
+        self.pos = end;
+        Ok(got)
+    }
+}
+
+impl<R, C> Seek for ChecksumReader<R, C>
+where
+    R: SafeRead + Seek,
+    C: Checksum,
+{
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.pos = self.inner.seek(pos)?;
+        Ok(self.pos)
+    }
+}
+
+impl<R, C> SafeRead for ChecksumReader<R, C>
+where
+    R: SafeRead,
+    C: Checksum,
+{
+    // Scans must not read ahead, or bytes past terminator would be counted too early. Seeking
+    // must go through `Seek` impl above anyway, to keep `pos` up to date.
+    fn as_seek(&mut self) -> Option<&mut dyn Seek> { None }
+
+    fn offset(&mut self) -> Option<u64> { Some(self.pos) }
+}
+
+/// Writer feeding all written bytes to a checksum
+pub struct ChecksumWriter<W, C> {
+    inner:  W,
+    csum:   C,
+    /// Position where checksum starts
+    start:  u64,
+    /// Current position in stream
+    pos:    u64,
+    /// Position up to which data was seen, whether fed to checksum or not
+    done:   u64,
+    /// Position just past last byte fed to checksum
+    hashed: u64,
+    /// Don't feed data to checksum
+    paused: bool,
+}
+
+impl<W, C> ChecksumWriter<W, C>
+where
+    W: SafeWrite,
+    C: Checksum,
+{
+    pub fn new(mut inner: W, csum: C) -> Result<Self> {
+        let pos = inner.tell()?;
+        Ok(Self {
+            inner,
+            csum,
+            start: pos,
+            pos,
+            done: pos,
+            hashed: pos,
+            paused: false,
+        })
+    }
+
+    pub fn get_ref(&self) -> &W { &self.inner }
+
+    pub fn into_inner(self) -> W { self.inner }
+
+    /// Restart checksum at current position
+    pub fn reset(&mut self) {
+        self.csum.reset();
+        self.start = self.pos;
+        self.done = self.pos;
+        self.hashed = self.pos;
+    }
+
+    /// Range of stream positions covered by checksum so far, ending at last byte fed to it.
+    /// A stored checksum passed through in between without [`Self::reset`] is inside the
+    /// range, but isn't counted.
+    pub fn covered(&self) -> std::ops::Range<u64> { self.start .. self.hashed }
+
+    /// Run `body` with checksum not counting data passing through
+    pub(crate) fn unhashed<T, F>(&mut self, body: F) -> Result<T>
+    where
+        F: FnOnce(&mut Self) -> Result<T>,
+    {
+        self.paused = true;
+        let res = body(self);
+        self.paused = false;
+        res
+    }
+
+    /// Checksum of data written so far
+    pub fn finalize(&self) -> C::Output { self.csum.finalize() }
+
+    /// Write running checksum, not counting it
+    pub fn write_checksum(&mut self, endian: Endian) -> Result<C::Output>
+    where
+        Self: be::SafeWriter<C::Output> + le::SafeWriter<C::Output>,
+    {
+        let val = self.finalize();
+
+        self.unhashed(|wr| match endian {
+            Endian::Big => be::SafeWriter::<C::Output>::write_auto(wr, val.clone()),
+            Endian::Little => le::SafeWriter::<C::Output>::write_auto(wr, val.clone()),
+        })?;
+
+        Ok(val)
+    }
+}
+
+impl<W, C> Write for ChecksumWriter<W, C>
+where
+    W: SafeWrite,
+    C: Checksum,
+{
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.pos < self.done && self.pos + buf.len() as u64 > self.start {
+            return Err(std::io::Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "can't overwrite data at 0x{:X} already counted in checksum",
+                    self.pos
+                ),
+            ));
+        }
+        let put = self.inner.write(buf)?;
+        let end = self.pos + put as u64;
+        if self.paused {
+            self.done = self.done.max(end);
+        } else if end > self.done {
+            let skip = self.done.saturating_sub(self.pos) as usize;
+            self.csum.update(&buf[skip .. put]);
+            self.done = end;
+            self.hashed = end;
+        }
+        self.pos = end;
+        Ok(put)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> { self.inner.flush() }
+}
+
+impl<W, C> Seek for ChecksumWriter<W, C>
+where
+    W: SafeWrite,
+    C: Checksum,
+{
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.pos = self.inner.seek(pos)?;
+        Ok(self.pos)
+    }
+}
@@ -0,0 +1,139 @@
+//! Cryptographic digests over read or written data (feature `digest`)
+//!
+//! Any RustCrypto [`Digest`] plugs into [`crate::checksum`] adaptors through [`DigestSum`],
+//! sharing their rules on read-ahead and back-patching.
+//!
+//! ```
+//! use pakr_typed_io::*;
+//! use pakr_typed_io::digest::{DigestReader, DigestSum, DigestWriter};
+//!
+//! use sha2::Sha256;
+//! use std::io::Cursor;
+//!
+//! fn main() -> Result<(), eyre::Report> {
+//!     let mut buf = vec![];
+//!     {
+//!         let mut wr = DigestWriter::new(Cursor::new(&mut buf), DigestSum::<Sha256>::new())?;
+//!         wr.write_exact(b"firmware image")?;
+//!         wr.write_digest()?;
+//!     }
+//!     assert_eq!(14 + 32, buf.len());
+//!
+//!     let mut rd = DigestReader::new(Cursor::new(&buf), DigestSum::<Sha256>::new())?;
+//!     rd.read_as_vec(14)?;
+//!     rd.verify_stored_digest()?;
+//!
+//!     // Mismatch reports range covered by digest
+//!     let mut rd = DigestReader::new(Cursor::new(&buf), DigestSum::<Sha256>::new())?;
+//!     rd.read_as_vec(4)?;
+//!     let err = rd.verify_digest(&[0; 32]).unwrap_err().to_string();
+//!     assert!(err.starts_with("digest over 0x0..0x4 is 0x"));
+//!
+//!     // Range doesn't include stored digest itself
+//!     let mut buf = buf.clone();
+//!     buf[0] = b'F';
+//!     let mut rd = DigestReader::new(Cursor::new(&buf), DigestSum::<Sha256>::new())?;
+//!     rd.read_as_vec(14)?;
+//!     let err = rd.verify_stored_digest().unwrap_err().to_string();
+//!     assert!(err.starts_with("digest over 0x0..0xE is 0x"));
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use std::{fmt::Write as _, ops::Range};
+
+use ::digest::{Digest, Output};
+use eyre::{eyre, Result};
+
+use crate::{
+    checksum::{Checksum, ChecksumReader, ChecksumWriter},
+    SafeRead, SafeWrite,
+};
+
+/// [`Checksum`] computed by a RustCrypto [`Digest`]
+#[derive(Clone, Default)]
+pub struct DigestSum<D> {
+    hasher: D,
+}
+
+impl<D> DigestSum<D>
+where
+    D: Digest,
+{
+    pub fn new() -> Self { Self { hasher: D::new() } }
+}
+
+impl<D> Checksum for DigestSum<D>
+where
+    D: Digest + Clone,
+{
+    type Output = Output<D>;
+
+    fn update(&mut self, data: &[u8]) { Digest::update(&mut self.hasher, data); }
+
+    fn reset(&mut self) { self.hasher = D::new(); }
+
+    fn finalize(&self) -> Output<D> { self.hasher.clone().finalize() }
+}
+
+/// Reader hashing all read bytes
+pub type DigestReader<R, D> = ChecksumReader<R, DigestSum<D>>;
+
+/// Writer hashing all written bytes
+pub type DigestWriter<W, D> = ChecksumWriter<W, DigestSum<D>>;
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut res, v| {
+        let _ = write!(res, "{v:02X}");
+        res
+    })
+}
+
+fn compare(actual: &[u8], range: Range<u64>, expected: &[u8]) -> Result<()> {
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(eyre!(
+            "digest over 0x{:X}..0x{:X} is 0x{}, expected 0x{}",
+            range.start,
+            range.end,
+            hex(actual),
+            hex(expected)
+        ))
+    }
+}
+
+impl<R, D> ChecksumReader<R, DigestSum<D>>
+where
+    R: SafeRead,
+    D: Digest + Clone,
+{
+    /// Compare digest of data read so far with `expected`
+    pub fn verify_digest(&self, expected: &[u8]) -> Result<()> {
+        compare(&self.finalize(), self.covered(), expected)
+    }
+
+    /// Read stored digest, not hashing it, and compare with digest of data read so far
+    pub fn verify_stored_digest(&mut self) -> Result<()> {
+        // Range has to be taken before stored digest is read past it
+        let range = self.covered();
+        let actual = self.finalize();
+        let size = <D as Digest>::output_size();
+        let stored = self.unhashed(|rd| rd.read_as_vec(size))?;
+        compare(&actual, range, &stored)
+    }
+}
+
+impl<W, D> ChecksumWriter<W, DigestSum<D>>
+where
+    W: SafeWrite,
+    D: Digest + Clone,
+{
+    /// Write digest of data written so far, not hashing it
+    pub fn write_digest(&mut self) -> Result<Output<D>> {
+        let val = self.finalize();
+        self.unhashed(|wr| wr.write_exact(&val))?;
+        Ok(val)
+    }
+}
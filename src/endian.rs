@@ -0,0 +1,165 @@
+//! Byte order selected at runtime
+//!
+//! Formats like TIFF (`II`/`MM`), ELF (`EI_DATA`) or pcap (magic number order) declare their
+//! byte order in the header, so it can't be chosen by importing [`crate::be`] or [`crate::le`].
+//! [`EndianReader`] and [`EndianWriter`] wrap a stream and dispatch `read_auto`/`write_auto`
+//! on an [`Endian`] value instead.
+//!
+//! ```
+//! use pakr_typed_io::*;
+//! use pakr_typed_io::endian::{Endian, EndianReader};
+//!
+//! use std::io::Cursor;
+//!
+//! fn main() -> Result<(), eyre::Report> {
+//!     let buf = vec![b'I', b'I', 42, 0, 8, 0, 0, 0];
+//!     let mut cur = Cursor::new(&buf);
+//!
+//!     let endian = match &cur.read_as_vec(2)?[..] {
+//!         b"II" => Endian::Little,
+//!         b"MM" => Endian::Big,
+//!         _ => return Err(eyre::eyre!("not a TIFF file")),
+//!     };
+//!
+//!     let mut rd = EndianReader::new(cur, endian);
+//!     let magic: u16 = rd.read_auto()?;
+//!     let ifd: u32 = rd.read_auto()?;
+//!
+//!     assert_eq!(42, magic);
+//!     assert_eq!(8, ifd);
+//!     Ok(())
+//! }
+//! ```
+
+use std::io::{BufRead, Read, Seek, SeekFrom, Write};
+
+use eyre::Result;
+
+use crate::{be, le, SafeRead, SafeWrite};
+
+/// Byte order of multi-byte values
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Endian {
+    Big,
+    Little,
+}
+
+impl Endian {
+    /// Byte order of the host
+    #[cfg(target_endian = "big")]
+    pub const NATIVE: Endian = Endian::Big;
+
+    /// Byte order of the host
+    #[cfg(target_endian = "little")]
+    pub const NATIVE: Endian = Endian::Little;
+}
+
+/// Reader with byte order of `read_auto` chosen at runtime
+pub struct EndianReader<R> {
+    inner:  R,
+    endian: Endian,
+}
+
+impl<R> EndianReader<R>
+where
+    R: SafeRead,
+{
+    pub fn new(inner: R, endian: Endian) -> Self { Self { inner, endian } }
+
+    pub fn endian(&self) -> Endian { self.endian }
+
+    pub fn set_endian(&mut self, endian: Endian) { self.endian = endian; }
+
+    pub fn get_ref(&self) -> &R { &self.inner }
+
+    pub fn get_mut(&mut self) -> &mut R { &mut self.inner }
+
+    pub fn into_inner(self) -> R { self.inner }
+
+    /// Read value of type `T` in currently selected byte order
+    pub fn read_auto<T>(&mut self) -> Result<T>
+    where
+        R: be::SafeReader<T> + le::SafeReader<T>,
+    {
+        match self.endian {
+            Endian::Big => be::SafeReader::<T>::read_auto(&mut self.inner),
+            Endian::Little => le::SafeReader::<T>::read_auto(&mut self.inner),
+        }
+    }
+}
+
+impl<R> Read for EndianReader<R>
+where
+    R: SafeRead,
+{
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> { self.inner.read(buf) }
+}
+
+impl<R> Seek for EndianReader<R>
+where
+    R: SafeRead + Seek,
+{
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> { self.inner.seek(pos) }
+}
+
+impl<R> SafeRead for EndianReader<R>
+where
+    R: SafeRead,
+{
+    fn as_seek(&mut self) -> Option<&mut dyn Seek> { self.inner.as_seek() }
+
+    fn as_buf_read(&mut self) -> Option<&mut dyn BufRead> { self.inner.as_buf_read() }
+
+    fn offset(&mut self) -> Option<u64> { self.inner.offset() }
+}
+
+/// Writer with byte order of `write_auto` chosen at runtime
+pub struct EndianWriter<W> {
+    inner:  W,
+    endian: Endian,
+}
+
+impl<W> EndianWriter<W>
+where
+    W: SafeWrite,
+{
+    pub fn new(inner: W, endian: Endian) -> Self { Self { inner, endian } }
+
+    pub fn endian(&self) -> Endian { self.endian }
+
+    pub fn set_endian(&mut self, endian: Endian) { self.endian = endian; }
+
+    pub fn get_ref(&self) -> &W { &self.inner }
+
+    pub fn get_mut(&mut self) -> &mut W { &mut self.inner }
+
+    pub fn into_inner(self) -> W { self.inner }
+
+    /// Write value of type `T` in currently selected byte order
+    pub fn write_auto<T>(&mut self, val: T) -> Result<()>
+    where
+        W: be::SafeWriter<T> + le::SafeWriter<T>,
+    {
+        match self.endian {
+            Endian::Big => be::SafeWriter::<T>::write_auto(&mut self.inner, val),
+            Endian::Little => le::SafeWriter::<T>::write_auto(&mut self.inner, val),
+        }
+    }
+}
+
+impl<W> Write for EndianWriter<W>
+where
+    W: SafeWrite,
+{
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> { self.inner.write(buf) }
+
+    fn flush(&mut self) -> std::io::Result<()> { self.inner.flush() }
+}
+
+impl<W> Seek for EndianWriter<W>
+where
+    W: SafeWrite,
+{
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> { self.inner.seek(pos) }
+}
+
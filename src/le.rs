@@ -1,6 +1,6 @@
-use eyre::Result;
+use eyre::{eyre, Result};
 
-use super::{SafeRead, SafeWrite};
+use super::{bitfield::Bitfield, SafeRead, SafeWrite};
 
 pub trait SafeReader<T>: SafeRead {
     fn read_auto(&mut self) -> Result<T>;
@@ -149,3 +149,107 @@ where
 {
     fn write_auto(&mut self, val: i128) -> Result<()> { self.write_i128_le(val) }
 }
+
+impl<SR> SafeReader<bool> for SR
+where
+    SR: SafeRead,
+{
+    fn read_auto(&mut self) -> Result<bool> {
+        match self.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            v => Err(eyre!("value 0x{:X} is not a valid bool", v)),
+        }
+    }
+}
+
+impl<SW> SafeWriter<bool> for SW
+where
+    SW: SafeWrite,
+{
+    fn write_auto(&mut self, val: bool) -> Result<()> { self.write_u8(val as u8) }
+}
+
+impl<SR, T, const N: usize> SafeReader<[T; N]> for SR
+where
+    SR: SafeReader<T>,
+{
+    fn read_auto(&mut self) -> Result<[T; N]> {
+        let mut buf = Vec::with_capacity(N);
+        for _ in 0 .. N {
+            buf.push(SafeReader::<T>::read_auto(self)?);
+        }
+        buf.try_into()
+            .map_err(|_| eyre!("read_auto array length mismatch"))
+    }
+}
+
+impl<SW, T, const N: usize> SafeWriter<[T; N]> for SW
+where
+    SW: SafeWriter<T>,
+{
+    fn write_auto(&mut self, val: [T; N]) -> Result<()> {
+        for v in val {
+            SafeWriter::<T>::write_auto(self, v)?;
+        }
+        Ok(())
+    }
+}
+
+macro_rules! tuple_impls {
+    ($($typ:ident $var:ident),+) => {
+        impl<SR, $($typ),+> SafeReader<($($typ,)+)> for SR
+        where
+            SR: $(SafeReader<$typ> +)+,
+        {
+            fn read_auto(&mut self) -> Result<($($typ,)+)> {
+                Ok(($(SafeReader::<$typ>::read_auto(self)?,)+))
+            }
+        }
+
+        impl<SW, $($typ),+> SafeWriter<($($typ,)+)> for SW
+        where
+            SW: $(SafeWriter<$typ> +)+,
+        {
+            fn write_auto(&mut self, val: ($($typ,)+)) -> Result<()> {
+                let ($($var,)+) = val;
+                $(SafeWriter::<$typ>::write_auto(self, $var)?;)+
+                Ok(())
+            }
+        }
+    };
+}
+
+tuple_impls!(A a);
+tuple_impls!(A a, B b);
+tuple_impls!(A a, B b, C c);
+tuple_impls!(A a, B b, C c, D d);
+tuple_impls!(A a, B b, C c, D d, E e);
+tuple_impls!(A a, B b, C c, D d, E e, F f);
+tuple_impls!(A a, B b, C c, D d, E e, F f, G g);
+tuple_impls!(A a, B b, C c, D d, E e, F f, G g, H h);
+tuple_impls!(A a, B b, C c, D d, E e, F f, G g, H h, I i);
+tuple_impls!(A a, B b, C c, D d, E e, F f, G g, H h, I i, J j);
+tuple_impls!(A a, B b, C c, D d, E e, F f, G g, H h, I i, J j, K k);
+tuple_impls!(A a, B b, C c, D d, E e, F f, G g, H h, I i, J j, K k, L l);
+
+impl<SR, B> SafeReader<B> for SR
+where
+    SR: SafeReader<B::Raw>,
+    B: Bitfield,
+{
+    fn read_auto(&mut self) -> Result<B> {
+        let raw = SafeReader::<B::Raw>::read_auto(self)?;
+        Ok(B::from_raw(raw))
+    }
+}
+
+impl<SW, B> SafeWriter<B> for SW
+where
+    SW: SafeWriter<B::Raw>,
+    B: Bitfield,
+{
+    fn write_auto(&mut self, val: B) -> Result<()> {
+        SafeWriter::<B::Raw>::write_auto(self, val.to_raw())
+    }
+}
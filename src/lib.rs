@@ -98,6 +98,161 @@
 //! }
 //! ```
 //!
+//! ### Compound types
+//! ```
+//! // Automatically selected data sizes as LE
+//! use pakr_typed_io::le::*;
+//!
+//! use eyre::Result;
+//! use std::io::Cursor;
+//!
+//! fn main() -> Result<(), eyre::Report> {
+//!     let mut buf = vec![];
+//!     {
+//!         let mut cur = Cursor::new(&mut buf);
+//!
+//!         // Arrays, tuples and bools are written element by element
+//!         cur.write_auto((*b"PAKR", 2_u16, [1_u8, 0], true))?;
+//!     }
+//!
+//!     assert_eq!(vec![b'P', b'A', b'K', b'R', 2, 0, 1, 0, 1], buf);
+//!
+//!     let mut cur = Cursor::new(&buf);
+//!     let (magic, ver, flags): ([u8; 4], u16, [bool; 2]) = cur.read_auto()?;
+//!     assert_eq!((*b"PAKR", 2, [true, false]), (magic, ver, flags));
+//!
+//!     // Bools are strict, anything but 0 or 1 is an error
+//!     let mut cur = Cursor::new(&[2_u8][..]);
+//!     assert!(SafeReader::<bool>::read_auto(&mut cur).is_err());
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! ### Bulk data
+//! ```
+//! use pakr_typed_io::*;
+//!
+//! use eyre::Result;
+//! use std::io::Cursor;
+//!
+//! fn main() -> Result<(), eyre::Report> {
+//!     let samples: Vec<u32> = (0 .. 10_000).collect();
+//!
+//!     let mut buf = vec![];
+//!     Cursor::new(&mut buf).write_u32_be_slice(&samples)?;
+//!     assert_eq!(vec![0, 0, 0, 1], buf[4 .. 8]);
+//!
+//!     let mut cur = Cursor::new(&buf);
+//!     assert_eq!(samples, cur.read_vec_u32be(samples.len())?);
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! ### Back-patching
+//! ```
+//! use pakr_typed_io::*;
+//!
+//! use eyre::Result;
+//! use std::io::Cursor;
+//!
+//! fn main() -> Result<(), eyre::Report> {
+//!     let mut buf = vec![];
+//!     {
+//!         let mut cur = Cursor::new(&mut buf);
+//!
+//!         // Size isn't known until data is written
+//!         let size = cur.reserve_u32_be()?;
+//!         let start = cur.tell()?;
+//!         cur.write_exact(b"payload")?;
+//!         let end = cur.tell()?;
+//!
+//!         cur.fill(size, (end - start) as u32)?;
+//!         cur.write_u8(0xFF)?;
+//!     }
+//!
+//!     assert_eq!(b"\0\0\0\x07payload\xff", &buf[..]);
+//!
+//!     // Scoped sections do the same, and can be nested
+//!     let mut buf = vec![];
+//!     {
+//!         let mut cur = Cursor::new(&mut buf);
+//!         cur.write_exact(b"RIFF")?;
+//!         cur.with_length_prefix(LenFmt::U32Le, |w| {
+//!             w.write_exact(b"WAVE")?;
+//!             w.with_length_prefix_inclusive(LenFmt::U8, |w| w.write_u16_be(0xABCD))
+//!         })?;
+//!     }
+//!
+//!     assert_eq!(b"RIFF\x07\0\0\0WAVE\x03\xab\xcd", &buf[..]);
+//!     Ok(())
+//! }
+//! ```
+//!
+//! ### Strings
+//! ```
+//! use pakr_typed_io::*;
+//!
+//! use eyre::Result;
+//! use std::io::Cursor;
+//!
+//! fn main() -> Result<(), eyre::Report> {
+//!     let mut buf = vec![];
+//!     {
+//!         let mut cur = Cursor::new(&mut buf);
+//!         cur.write_cstr("zażółć")?;
+//!         cur.write_exact(b"\xff\0unterminated")?;
+//!
+//!         // Embedded terminator can't be written
+//!         assert!(cur.write_cstr("a\0b").is_err());
+//!     }
+//!
+//!     let mut cur = Cursor::new(&buf);
+//!     assert_eq!("zażółć", cur.read_cstr_utf8()?);
+//!
+//!     // Strict reads fail on invalid UTF-8 and report where the string started
+//!     assert_eq!(
+//!         cur.read_cstr_utf8().unwrap_err().to_string(),
+//!         "invalid UTF-8 in string at 0xB: invalid utf-8 sequence of 1 bytes from index 0"
+//!     );
+//!
+//!     // Bounded reads fail if there is no terminator in sight
+//!     assert_eq!(
+//!         format!("{:#}", cur.read_cstr_max(4).unwrap_err()),
+//!         "can't read string at 0xD: read_until overflow - no terminator 0x0 within 4 bytes"
+//!     );
+//!     assert_eq!(
+//!         cur.read_cstr_bytes().unwrap_err().to_string(),
+//!         "can't read string at 0xD"
+//!     );
+//!
+//!     // Fixed-width and length-prefixed fields
+//!     let mut buf = vec![];
+//!     {
+//!         let mut cur = Cursor::new(&mut buf);
+//!         cur.write_fixed_str("name", 8, Padding::Zero)?;
+//!         cur.write_fixed_str("NAME", 6, Padding::Space)?;
+//!         cur.write_pstring_u8("short")?;
+//!         cur.write_string_u16_be("longer")?;
+//!         cur.write_string_leb128("varint")?;
+//!
+//!         // Strings not fitting their field are rejected
+//!         assert!(cur.write_fixed_str("too long", 4, Padding::Zero).is_err());
+//!         assert!(cur.write_pstring_u8(&"x".repeat(256)).is_err());
+//!     }
+//!
+//!     let mut cur = Cursor::new(&buf);
+//!     assert_eq!("name", cur.read_fixed_str(8, Padding::Zero)?);
+//!     assert_eq!("NAME", cur.read_fixed_str(6, Padding::Space)?);
+//!     assert_eq!("short", cur.read_pstring_u8()?);
+//!     assert_eq!("longer", cur.read_string_u16be()?);
+//!     assert_eq!("varint", cur.read_string_leb128()?);
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
 //! ### Verifying data
 //!
 //! ```
@@ -155,13 +310,74 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! Floats, `bool`, `char` and byte strings have validators of their own.
+//!
+//! ```
+//! use pakr_typed_io::*;
+//! use pakr_typed_io::validator::{BytesValidator, CharValidator, FloatValidator};
+//!
+//! use eyre::Result;
+//! use std::io::Cursor;
+//!
+//! fn main() -> Result<(), eyre::Report> {
+//!     let buf = b"\x7fELF\x02\0\0\0\x01\x3f\xc0\0\0";
+//!     let mut cur = Cursor::new(&buf[..]);
+//!
+//!     // Magic numbers and reserved areas
+//!     let ident = cur.read_as_vec(8)?.validate_starts_with(b"\x7fELF")?;
+//!     assert_eq!(
+//!         ident.clone().validate_all_zero_ctx("e_ident").unwrap_err().to_string(),
+//!         "value e_ident=0x7F at offset 0x0 is not zero"
+//!     );
+//!     assert_eq!(
+//!         ident[4 ..].validate_len_ctx(2, "pad").unwrap_err().to_string(),
+//!         "value pad=[02, 00, 00, 00] length 0x4 not equal to expected 0x2"
+//!     );
+//!     assert_eq!(
+//!         cur.read_as_vec(1)?.validate_equals(b"\x02").unwrap_err().to_string(),
+//!         "value [01] not equal to expected [02]"
+//!     );
+//!
+//!     let scale = f32::from_bits(cur.read_u32be()?).validate_finite()?;
+//!     scale.validate_approx_eq(1.5, 0.001)?;
+//!     assert_eq!(
+//!         scale.validate_in_range(0.0 .. 1.0).unwrap_err().to_string(),
+//!         "value 1.5 not in expected range 0.0..1.0"
+//!     );
+//!     assert!(f64::NAN.validate_not_nan().is_err());
+//!     f64::INFINITY.validate_approx_eq(f64::INFINITY, 0.001)?;
+//!     assert!(f64::INFINITY.validate_approx_eq(f64::NEG_INFINITY, 0.001).is_err());
+//!
+//!     assert_eq!(
+//!         'ł'.validate_in_range('a' ..= 'z').unwrap_err().to_string(),
+//!         "value U+0142 not in expected range 'a'..='z'"
+//!     );
+//!     Ok(())
+//! }
+//! ```
 
+#[cfg(feature = "tokio")]
+pub mod async_io;
 pub mod be;
+pub mod bitfield;
+pub mod bits;
+pub mod checksum;
+#[cfg(feature = "digest")]
+pub mod digest;
+pub mod endian;
 pub mod le;
+#[cfg(feature = "mmap")]
+pub mod mmap;
+pub mod ne;
 pub mod reader;
+pub mod slice;
+pub mod text;
 pub mod tlv;
 pub mod validator;
 pub mod writer;
 
+#[doc(hidden)]
+pub use eyre;
 pub use reader::*;
 pub use writer::*;
@@ -0,0 +1,132 @@
+//! Memory-mapped file reader (feature `mmap`)
+//!
+//! [`MmapReader`] serves reads from a mapping instead of issuing a syscall each, knows its
+//! length up front and lends out [`SliceReader`]s borrowing straight from the mapping.
+//!
+//! ```
+//! use pakr_typed_io::*;
+//! use pakr_typed_io::mmap::MmapReader;
+//!
+//! use std::fs::{self, File};
+//!
+//! fn main() -> Result<(), eyre::Report> {
+//!     let path = std::env::temp_dir().join("pakr-typed-io-mmap-doctest.bin");
+//!     fs::write(&path, b"\x00\x00\x00\x06hello\0world\0")?;
+//!
+//!     // Safety: file isn't modified by anyone while mapped
+//!     let mut rd = unsafe { MmapReader::new(File::open(&path)?)? };
+//!     assert_eq!(16, rd.len());
+//!
+//!     let size = rd.read_u32be()?;
+//!     assert_eq!("hello", rd.read_fixed_str(size as usize, Padding::Zero)?);
+//!
+//!     // Zero-copy access to any part of file
+//!     let mut names = rd.window(10 .. 16)?;
+//!     assert_eq!(c"world", names.read_cstr()?);
+//!     assert!(rd.window(10 .. 17).is_err());
+//!
+//!     rd.verify_len()?;
+//!     drop(rd);
+//!     fs::remove_file(&path)?;
+//!     Ok(())
+//! }
+//! ```
+
+use std::{
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    ops::Range,
+};
+
+use eyre::{eyre, Result};
+use memmap2::Mmap;
+
+use crate::{
+    slice::{seek_in, SliceReader},
+    SafeRead,
+};
+
+/// Reader over read-only mapping of a whole file
+pub struct MmapReader {
+    file: File,
+    map:  Mmap,
+    pos:  usize,
+}
+
+impl MmapReader {
+    /// Map whole `file`
+    ///
+    /// # Safety
+    ///
+    /// Mapped data must not change while reader exists. In particular, if file is truncated
+    /// by another process, touching mapped pages past new end kills the process with
+    /// `SIGBUS`. Where that is possible, call [`MmapReader::verify_len`] before touching
+    /// large regions, which turns truncation that already happened into an error.
+    pub unsafe fn new(file: File) -> Result<Self> {
+        let map = Mmap::map(&file)?;
+        Ok(Self { file, map, pos: 0 })
+    }
+
+    /// Size of mapped file, known without a syscall
+    pub fn len(&self) -> usize { self.map.len() }
+
+    pub fn is_empty(&self) -> bool { self.map.is_empty() }
+
+    pub fn position(&self) -> usize { self.pos }
+
+    /// Whole mapped file
+    pub fn as_bytes(&self) -> &[u8] { &self.map }
+
+    /// Fail if file got shorter than its mapping
+    pub fn verify_len(&self) -> Result<()> {
+        let now = self.file.metadata()?.len();
+        if now < self.map.len() as u64 {
+            return Err(eyre!(
+                "file truncated to 0x{now:X} bytes, 0x{:X} mapped",
+                self.map.len()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Zero-copy reader over `range` of file, independent of reader position
+    pub fn window(&self, range: Range<usize>) -> Result<SliceReader<'_>> {
+        match self.map.get(range.clone()) {
+            Some(data) => Ok(SliceReader::new(data)),
+            None => Err(eyre!(
+                "window 0x{:X}..0x{:X} out of file of 0x{:X} bytes",
+                range.start,
+                range.end,
+                self.map.len()
+            )),
+        }
+    }
+
+    /// Zero-copy reader over rest of file, starting at current position
+    pub fn rest(&self) -> SliceReader<'_> {
+        SliceReader::new(&self.map[self.pos.min(self.map.len()) ..])
+    }
+}
+
+impl Read for MmapReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let src = &self.map[self.pos.min(self.map.len()) ..];
+        let got = src.len().min(buf.len());
+        buf[.. got].copy_from_slice(&src[.. got]);
+        self.pos += got;
+        Ok(got)
+    }
+}
+
+impl Seek for MmapReader {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.pos = seek_in(self.map.len(), self.pos, pos)?;
+        Ok(self.pos as u64)
+    }
+}
+
+impl SafeRead for MmapReader {
+    fn as_seek(&mut self) -> Option<&mut dyn Seek> { Some(self) }
+
+    fn offset(&mut self) -> Option<u64> { Some(self.pos as u64) }
+}
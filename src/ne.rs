@@ -0,0 +1,46 @@
+//! Automatically sized reads and writes in host byte order
+//!
+//! Re-exports [`crate::be`] or [`crate::le`] depending on `target_endian`, so the traits are
+//! the same ones and behave identically. Meant for in-memory dumps, shared memory and
+//! `/proc` binary files, which are never byte-swapped.
+//!
+//! ```
+//! use pakr_typed_io::ne::*;
+//!
+//! use std::io::Cursor;
+//!
+//! fn main() -> Result<(), eyre::Report> {
+//!     let buf = 0x0102_0304_u32.to_ne_bytes().to_vec();
+//!     let mut cur = Cursor::new(&buf);
+//!
+//!     let x: u32 = cur.read_auto()?;
+//!     assert_eq!(0x0102_0304, x);
+//!
+//!     let mut out = vec![];
+//!     Cursor::new(&mut out).write_auto(x)?;
+//!     assert_eq!(buf, out);
+//!
+//!     // Fixed bytes, so the test catches wrong module being re-exported
+//!     let mut cur = Cursor::new(&[1_u8, 2, 3, 4][..]);
+//!     let x: u32 = cur.read_auto()?;
+//!     #[cfg(target_endian = "little")]
+//!     assert_eq!(0x0403_0201, x);
+//!     #[cfg(target_endian = "big")]
+//!     assert_eq!(0x0102_0304, x);
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! Tested on little-endian hosts only, the big-endian branch is not cross-checked.
+
+#[cfg(target_endian = "big")]
+pub use crate::be::*;
+#[cfg(target_endian = "little")]
+pub use crate::le::*;
+
+// Any other byte order would leave this module empty
+const _: () = assert!(
+    cfg!(target_endian = "big") || cfg!(target_endian = "little"),
+    "unsupported target byte order"
+);
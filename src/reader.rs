@@ -1,33 +1,337 @@
 use std::{
+    fmt,
     fs::File,
-    io::{Cursor, Read, Seek, SeekFrom},
+    io::{BufRead, BufReader, Cursor, ErrorKind, Read, Seek, SeekFrom},
 };
 
-use eyre::{eyre, Result};
+use eyre::{eyre, Result, WrapErr};
 
-impl SafeRead for Cursor<Vec<u8>> {}
+use crate::text::{decode_latin1, decode_mutf8, decode_utf16};
 
-impl SafeRead for Cursor<&Vec<u8>> {}
+/// Size of intermediate buffer used by bulk reads
+const BULK_CHUNK: usize = 4096;
 
-impl SafeRead for Cursor<&[u8]> {}
+/// Size of lookahead used when scanning for terminators
+const SCAN_CHUNK: usize = 256;
 
-impl SafeRead for &mut File {}
+macro_rules! bulk_read {
+    ($into:ident, $vec:ident, $typ:ty, $conv:ident) => {
+        /// Fill whole `dst` with consecutive values, decoding one buffer at a time
+        fn $into(&mut self, dst: &mut [$typ]) -> Result<()> {
+            const SIZE: usize = std::mem::size_of::<$typ>();
+            let mut buf = [0u8; BULK_CHUNK];
+            for part in dst.chunks_mut(BULK_CHUNK / SIZE) {
+                let bytes = &mut buf[.. part.len() * SIZE];
+                self.read_exact(bytes)?;
+                for (val, raw) in part.iter_mut().zip(bytes.chunks_exact(SIZE)) {
+                    *val = <$typ>::$conv(raw.try_into().unwrap());
+                }
+            }
+            Ok(())
+        }
 
-impl SafeRead for File {}
+        /// Read `count` consecutive values
+        fn $vec(&mut self, count: usize) -> Result<Vec<$typ>> {
+            let mut buf = vec![0; count];
+            self.$into(&mut buf)?;
+            Ok(buf)
+        }
+    };
+}
 
-#[allow(clippy::len_without_is_empty)]
-pub trait SafeRead: Read + Seek {
-    fn tell(&mut self) -> Result<u64> {
-        let pos = self.stream_position()?;
-        Ok(pos)
+/// Filler of unused tail of fixed-width string field
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Padding {
+    /// String ends at first zero byte, or fills whole field
+    Zero,
+    /// Trailing spaces are not part of the string
+    Space,
+}
+
+/// Stream offset for error messages, formatted as ` at 0x...` if known
+#[derive(Copy, Clone)]
+pub(crate) struct At(pub(crate) Option<u64>);
+
+impl fmt::Display for At {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Some(pos) => write!(f, " at 0x{pos:X}"),
+            None => Ok(()),
+        }
     }
+}
 
-    fn len(&mut self) -> Result<usize> {
-        let here = self.tell()?;
-        let end_pos = self.seek(SeekFrom::End(0))?;
-        self.seek(SeekFrom::Start(here))?;
-        Ok(end_pos as usize)
+fn utf8_at(bytes: Vec<u8>, start: At) -> Result<String> {
+    String::from_utf8(bytes)
+        .map_err(|e| eyre!("invalid UTF-8 in string{start}: {}", e.utf8_error()))
+}
+
+/// Read UTF-16 code units up to zero unit, like [`SafeRead::read_until_max`] does for bytes
+fn read_utf16_until_zero<SR>(
+    src: &mut SR,
+    max: usize,
+    conv: fn([u8; 2]) -> u16,
+) -> Result<Vec<u16>>
+where
+    SR: SafeRead + ?Sized,
+{
+    let start = src.offset();
+    // Files may be pipes, which have `Seek` but fail to use it
+    let seekable = start.is_some() && src.as_seek().is_some();
+    let mut units = Vec::new();
+    let mut chunk = [0u8; SCAN_CHUNK];
+    loop {
+        // Never read more than one unit past `max`, and one unit at a time if can't seek back
+        let want = if seekable {
+            (max - units.len()).saturating_add(1).min(SCAN_CHUNK / 2) * 2
+        } else {
+            2
+        };
+        let mut got = match src.read(&mut chunk[.. want]) {
+            Ok(0) => return Err(std::io::Error::from(ErrorKind::UnexpectedEof).into()),
+            Ok(n) => n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        };
+        // Complete last unit; lone byte at end of stream is just overshoot
+        if got % 2 != 0 {
+            match src.read(&mut chunk[got .. got + 1]) {
+                Ok(0) if seekable => {},
+                Ok(0) => return Err(std::io::Error::from(ErrorKind::UnexpectedEof).into()),
+                Ok(_) => got += 1,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        for (idx, raw) in chunk[.. got].chunks_exact(2).enumerate() {
+            let unit = conv([raw[0], raw[1]]);
+            if unit == 0 {
+                let overshoot = (got - idx * 2 - 2) as i64;
+                if overshoot > 0 {
+                    src.as_seek()
+                        .expect("stream stopped being seekable")
+                        .seek(SeekFrom::Current(-overshoot))?;
+                }
+                return Ok(units);
+            }
+            if units.len() == max {
+                if let (true, Some(start)) = (seekable, start) {
+                    src.as_seek()
+                        .expect("stream stopped being seekable")
+                        .seek(SeekFrom::Start(start))?;
+                }
+                return Err(eyre!(
+                    "UTF-16 string overflow - no terminator within {max} units"
+                ));
+            }
+            units.push(unit);
+        }
+    }
+}
+
+/// Move bytes up to `end` from `src` buffer to `buf`, returning `false` on overflow
+fn scan_buffered(src: &mut dyn BufRead, buf: &mut Vec<u8>, end: u8, max: usize) -> Result<bool> {
+    loop {
+        let avail = match src.fill_buf() {
+            Ok([]) => return Err(std::io::Error::from(ErrorKind::UnexpectedEof).into()),
+            Ok(v) => v,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        };
+        let chunk = &avail[.. avail.len().min((max - buf.len()).saturating_add(1))];
+        match chunk.iter().position(|&v| v == end) {
+            Some(pos) => {
+                buf.extend_from_slice(&chunk[.. pos]);
+                src.consume(pos + 1);
+                return Ok(true);
+            },
+            None => {
+                let used = chunk.len();
+                let fits = buf.len() + used <= max;
+                if fits {
+                    buf.extend_from_slice(chunk);
+                }
+                src.consume(used);
+                if !fits {
+                    return Ok(false);
+                }
+            },
+        }
+    }
+}
+
+fn read_sized_str<SR>(src: &mut SR, start: At, size: u128, max: usize) -> Result<String>
+where
+    SR: SafeRead + ?Sized,
+{
+    if size > max as u128 {
+        return Err(eyre!(
+            "string{start} claims 0x{size:X} bytes, more than 0x{max:X} allowed"
+        ));
     }
+    let known = src.offset().is_some();
+    let bytes = if let (true, Some(seek)) = (known, src.as_seek()) {
+        let here = seek.stream_position()?;
+        let end = seek.seek(SeekFrom::End(0))?;
+        seek.seek(SeekFrom::Start(here))?;
+        let left = end.saturating_sub(here);
+        if size > left as u128 {
+            return Err(eyre!(
+                "string{start} claims 0x{size:X} bytes, only 0x{left:X} left"
+            ));
+        }
+        src.read_as_vec(size as usize)?
+    } else {
+        // Buffer grows with data actually received, not with the declared size
+        let mut buf = Vec::new();
+        (&mut *src).take(size as u64).read_to_end(&mut buf)?;
+        if (buf.len() as u128) < size {
+            return Err(std::io::Error::from(ErrorKind::UnexpectedEof).into());
+        }
+        buf
+    };
+    utf8_at(bytes, start)
+}
+
+impl<T> SafeRead for Cursor<T>
+where
+    T: AsRef<[u8]>,
+{
+    fn as_seek(&mut self) -> Option<&mut dyn Seek> { Some(self) }
+}
+
+impl SafeRead for File {
+    fn as_seek(&mut self) -> Option<&mut dyn Seek> { Some(self) }
+}
+
+impl SafeRead for &File {
+    fn as_seek(&mut self) -> Option<&mut dyn Seek> { Some(self) }
+}
+
+impl<R> SafeRead for BufReader<R>
+where
+    R: Read + Seek,
+{
+    fn as_seek(&mut self) -> Option<&mut dyn Seek> { Some(self) }
+
+    fn as_buf_read(&mut self) -> Option<&mut dyn BufRead> { Some(self) }
+}
+
+impl<R> SafeRead for &mut R
+where
+    R: SafeRead + ?Sized,
+{
+    fn as_seek(&mut self) -> Option<&mut dyn Seek> { (**self).as_seek() }
+
+    fn as_buf_read(&mut self) -> Option<&mut dyn BufRead> { (**self).as_buf_read() }
+
+    fn offset(&mut self) -> Option<u64> { (**self).offset() }
+}
+
+impl<R> SafeRead for Box<R>
+where
+    R: SafeRead + ?Sized,
+{
+    fn as_seek(&mut self) -> Option<&mut dyn Seek> { (**self).as_seek() }
+
+    fn as_buf_read(&mut self) -> Option<&mut dyn BufRead> { (**self).as_buf_read() }
+
+    fn offset(&mut self) -> Option<u64> { (**self).offset() }
+}
+
+/// Seekable stream usable as trait object, e.g. `Box<dyn ReadSeek>` (as `dyn Read + Seek`
+/// isn't allowed)
+pub trait ReadSeek: Read + Seek {}
+
+impl<T> ReadSeek for T where T: Read + Seek + ?Sized {}
+
+impl SafeRead for dyn ReadSeek + '_ {
+    fn as_seek(&mut self) -> Option<&mut dyn Seek> { Some(self) }
+}
+
+impl SafeRead for dyn ReadSeek + Send + '_ {
+    fn as_seek(&mut self) -> Option<&mut dyn Seek> { Some(self) }
+}
+
+/// Implement [`SafeRead`] for seekable types not covered by the crate
+///
+/// ```
+/// use pakr_typed_io::*;
+///
+/// use std::io::{Cursor, Read, Seek, SeekFrom};
+///
+/// struct Image(Cursor<Vec<u8>>);
+///
+/// impl Read for Image {
+///     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> { self.0.read(buf) }
+/// }
+///
+/// impl Seek for Image {
+///     fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> { self.0.seek(pos) }
+/// }
+///
+/// seekable_safe_read!(Image);
+///
+/// fn main() -> Result<(), eyre::Report> {
+///     let mut img = Image(Cursor::new(b"\x01\x02".to_vec()));
+///     assert_eq!(0x0102, img.read_u16be()?);
+///     Ok(())
+/// }
+/// ```
+#[macro_export]
+macro_rules! seekable_safe_read {
+    ($($typ:ty),+ $(,)?) => {
+        $(
+            impl $crate::SafeRead for $typ {
+                fn as_seek(&mut self) -> Option<&mut dyn ::std::io::Seek> { Some(self) }
+            }
+        )+
+    };
+}
+
+/// Typed reads, needing only [`Read`]
+///
+/// Seekable implementations override [`SafeRead::as_seek`], which lets error messages name
+/// stream offsets and terminator scans read ahead. Cursors, files, [`BufReader`], references
+/// and boxes are covered, other seekable types can opt in with [`seekable_safe_read!`] or be
+/// boxed as [`ReadSeek`] trait objects.
+/// [`BufReader`] also overrides [`SafeRead::as_buf_read`], so scans don't discard its buffer.
+/// Forward-only streams like pipes and sockets can be wrapped in [`CountingReader`] to get
+/// offsets.
+///
+/// ```
+/// use pakr_typed_io::*;
+///
+/// use std::io::{BufReader, BufWriter, Cursor};
+///
+/// fn main() -> Result<(), eyre::Report> {
+///     let mut out = BufWriter::new(Cursor::new(Vec::new()));
+///     out.write_u16_be(0xABCD)?;
+///     out.write_cstr("buffered")?;
+///     let buf = out.into_inner()?.into_inner();
+///
+///     let mut rd = BufReader::new(Cursor::new(buf.into_boxed_slice()));
+///     assert_eq!(0xABCD, (&mut rd).read_u16be()?);
+///
+///     let mut rd: Box<dyn ReadSeek + Send> = Box::new(rd);
+///     assert_eq!("buffered", rd.read_cstr_utf8()?);
+///     assert_eq!(11, rd.tell()?);
+///
+///     let mut rd: Box<dyn ReadSeek> = Box::new(Cursor::new(b"\x01\x02"));
+///     assert_eq!(0x0102, rd.read_u16be()?);
+///
+///     Ok(())
+/// }
+/// ```
+pub trait SafeRead: Read {
+    /// Access to seeking, if the stream supports it
+    fn as_seek(&mut self) -> Option<&mut dyn Seek> { None }
+
+    /// Access to internal buffer, if the stream has one. Terminator scans then look at the
+    /// buffer instead of reading ahead and seeking back.
+    fn as_buf_read(&mut self) -> Option<&mut dyn BufRead> { None }
+
+    /// Current position in stream, if known
+    fn offset(&mut self) -> Option<u64> { self.as_seek()?.stream_position().ok() }
 
     fn read_u8(&mut self) -> Result<u8> {
         let mut buf = [0u8; 1];
@@ -137,6 +441,23 @@ pub trait SafeRead: Read + Seek {
         Ok(i128::from_le_bytes(buf))
     }
 
+    bulk_read!(read_u16be_into, read_vec_u16be, u16, from_be_bytes);
+    bulk_read!(read_u16le_into, read_vec_u16le, u16, from_le_bytes);
+    bulk_read!(read_i16be_into, read_vec_i16be, i16, from_be_bytes);
+    bulk_read!(read_i16le_into, read_vec_i16le, i16, from_le_bytes);
+    bulk_read!(read_u32be_into, read_vec_u32be, u32, from_be_bytes);
+    bulk_read!(read_u32le_into, read_vec_u32le, u32, from_le_bytes);
+    bulk_read!(read_i32be_into, read_vec_i32be, i32, from_be_bytes);
+    bulk_read!(read_i32le_into, read_vec_i32le, i32, from_le_bytes);
+    bulk_read!(read_u64be_into, read_vec_u64be, u64, from_be_bytes);
+    bulk_read!(read_u64le_into, read_vec_u64le, u64, from_le_bytes);
+    bulk_read!(read_i64be_into, read_vec_i64be, i64, from_be_bytes);
+    bulk_read!(read_i64le_into, read_vec_i64le, i64, from_le_bytes);
+    bulk_read!(read_u128be_into, read_vec_u128be, u128, from_be_bytes);
+    bulk_read!(read_u128le_into, read_vec_u128le, u128, from_le_bytes);
+    bulk_read!(read_i128be_into, read_vec_i128be, i128, from_be_bytes);
+    bulk_read!(read_i128le_into, read_vec_i128le, i128, from_le_bytes);
+
     fn read_into(&mut self, buf: &mut [u8]) -> Result<()> {
         self.read_exact(buf)?;
         Ok(())
@@ -148,14 +469,104 @@ pub trait SafeRead: Read + Seek {
         Ok(buf)
     }
 
-    fn read_until(&mut self, end: u8) -> Result<Vec<u8>> {
+    fn read_until(&mut self, end: u8) -> Result<Vec<u8>> { self.read_until_max(end, usize::MAX) }
+
+    /// Read bytes up to `end` (consumed, but not returned), failing if there is more than
+    /// `max` bytes before it. Buffered streams are scanned in their buffer. On other seekable
+    /// streams data is scanned in chunks and the stream is rewound to just after the
+    /// terminator. On overflow seekable streams are rewound to where reading started. Other
+    /// streams, including ones where seeking fails, are read byte by byte.
+    ///
+    /// ```
+    /// use pakr_typed_io::*;
+    ///
+    /// use std::io::{BufReader, ErrorKind, Read, Seek, SeekFrom};
+    ///
+    /// // Like a pipe opened as file
+    /// struct Pipe(&'static [u8]);
+    ///
+    /// impl Read for Pipe {
+    ///     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> { self.0.read(buf) }
+    /// }
+    ///
+    /// impl Seek for Pipe {
+    ///     fn seek(&mut self, _: SeekFrom) -> std::io::Result<u64> {
+    ///         Err(ErrorKind::Unsupported.into())
+    ///     }
+    /// }
+    ///
+    /// seekable_safe_read!(Pipe);
+    ///
+    /// fn main() -> Result<(), eyre::Report> {
+    ///     let mut rd = BufReader::new(Pipe(b"abc\0def\0"));
+    ///     assert_eq!(b"abc", &rd.read_until_max(0, 3)?[..]);
+    ///     assert_eq!(b"def", &rd.read_until(0)?[..]);
+    ///
+    ///     // Nothing is read ahead if it can't be given back
+    ///     let mut rd = Pipe(b"abc\0d\0e\0\0\0\0\x02hi");
+    ///     assert_eq!("abc", rd.read_cstr_utf8()?);
+    ///     assert_eq!("de", rd.read_utf16le_cstr()?);
+    ///     assert_eq!("hi", rd.read_string_u16be()?);
+    ///     Ok(())
+    /// }
+    /// ```
+    fn read_until_max(&mut self, end: u8, max: usize) -> Result<Vec<u8>> {
+        let start = self.offset();
+        if let Some(src) = self.as_buf_read() {
+            let mut buf = Vec::new();
+            if scan_buffered(src, &mut buf, end, max)? {
+                return Ok(buf);
+            }
+            if let (Some(start), Some(seek)) = (start, self.as_seek()) {
+                seek.seek(SeekFrom::Start(start))?;
+            }
+            return Err(eyre!(
+                "read_until overflow - no terminator 0x{end:X} within {max} bytes"
+            ));
+        }
+        if start.is_none() || self.as_seek().is_none() {
+            let mut buf = Vec::new();
+            loop {
+                match self.read_u8()? {
+                    v if v == end => return Ok(buf),
+                    _ if buf.len() == max => {
+                        return Err(eyre!(
+                            "read_until overflow - no terminator 0x{end:X} within {max} bytes"
+                        ))
+                    },
+                    v => buf.push(v),
+                }
+            }
+        }
+
         let mut buf = Vec::new();
+        let mut chunk = [0u8; SCAN_CHUNK];
         loop {
-            match self.read_u8() {
-                Ok(v) if v == end => return Ok(buf),
-                Ok(v) => buf.push(v),
-                Err(e) => return Err(e),
+            let want = SCAN_CHUNK.min((max - buf.len()).saturating_add(1));
+            let got = match self.read(&mut chunk[.. want]) {
+                Ok(0) => return Err(std::io::Error::from(ErrorKind::UnexpectedEof).into()),
+                Ok(n) => n,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            };
+            let seek = self.as_seek().expect("stream stopped being seekable");
+            if let Some(pos) = chunk[.. got].iter().position(|&v| v == end) {
+                buf.extend_from_slice(&chunk[.. pos]);
+                let overshoot = (got - pos - 1) as i64;
+                if overshoot > 0 {
+                    seek.seek(SeekFrom::Current(-overshoot))?;
+                }
+                return Ok(buf);
+            }
+            if buf.len() + got > max {
+                if let Some(start) = start {
+                    seek.seek(SeekFrom::Start(start))?;
+                }
+                return Err(eyre!(
+                    "read_until overflow - no terminator 0x{end:X} within {max} bytes"
+                ));
             }
+            buf.extend_from_slice(&chunk[.. got]);
         }
     }
 
@@ -166,6 +577,181 @@ pub trait SafeRead: Read + Seek {
         }
     }
 
+    /// Read zero-terminated string as raw bytes
+    fn read_cstr_bytes(&mut self) -> Result<Vec<u8>> { self.read_cstr_bytes_max(usize::MAX) }
+
+    /// Read zero-terminated string as raw bytes, at most `max` bytes long (sans terminator)
+    fn read_cstr_bytes_max(&mut self, max: usize) -> Result<Vec<u8>> {
+        let start = At(self.offset());
+        self.read_until_max(0, max)
+            .wrap_err_with(|| format!("can't read string{start}"))
+    }
+
+    /// Read zero-terminated string, failing on invalid UTF-8
+    fn read_cstr_utf8(&mut self) -> Result<String> { self.read_cstr_max(usize::MAX) }
+
+    /// Read zero-terminated string, at most `max` bytes long (sans terminator), failing on
+    /// invalid UTF-8
+    fn read_cstr_max(&mut self, max: usize) -> Result<String> {
+        let start = At(self.offset());
+        let bytes = self.read_cstr_bytes_max(max)?;
+        utf8_at(bytes, start)
+    }
+
+    /// Read string stored in fixed-width field of `size` bytes
+    fn read_fixed_str(&mut self, size: usize, padding: Padding) -> Result<String> {
+        let start = At(self.offset());
+        let mut bytes = self.read_as_vec(size)?;
+        let used = match padding {
+            Padding::Zero => bytes.iter().position(|&v| v == 0).unwrap_or(size),
+            Padding::Space => bytes.iter().rposition(|&v| v != b' ').map_or(0, |p| p + 1),
+        };
+        bytes.truncate(used);
+        utf8_at(bytes, start)
+    }
+
+    /// Read string prefixed with `u8` length
+    fn read_pstring_u8(&mut self) -> Result<String> {
+        let start = At(self.offset());
+        let size = self.read_u8()?;
+        read_sized_str(self, start, size as u128, usize::MAX)
+    }
+
+    /// Read string prefixed with big-endian `u16` length
+    fn read_string_u16be(&mut self) -> Result<String> {
+        let start = At(self.offset());
+        let size = self.read_u16be()?;
+        read_sized_str(self, start, size as u128, usize::MAX)
+    }
+
+    /// Read string prefixed with little-endian `u16` length
+    fn read_string_u16le(&mut self) -> Result<String> {
+        let start = At(self.offset());
+        let size = self.read_u16le()?;
+        read_sized_str(self, start, size as u128, usize::MAX)
+    }
+
+    /// Read string prefixed with big-endian `u32` length
+    fn read_string_u32be(&mut self) -> Result<String> { self.read_string_u32be_max(usize::MAX) }
+
+    /// Read string prefixed with big-endian `u32` length, failing if it's longer than `max`
+    /// bytes
+    fn read_string_u32be_max(&mut self, max: usize) -> Result<String> {
+        let start = At(self.offset());
+        let size = self.read_u32be()?;
+        read_sized_str(self, start, size as u128, max)
+    }
+
+    /// Read string prefixed with little-endian `u32` length
+    fn read_string_u32le(&mut self) -> Result<String> { self.read_string_u32le_max(usize::MAX) }
+
+    /// Read string prefixed with little-endian `u32` length, failing if it's longer than `max`
+    /// bytes
+    fn read_string_u32le_max(&mut self, max: usize) -> Result<String> {
+        let start = At(self.offset());
+        let size = self.read_u32le()?;
+        read_sized_str(self, start, size as u128, max)
+    }
+
+    /// Read string prefixed with length encoded as in [`SafeRead::read_leb128`]
+    fn read_string_leb128(&mut self) -> Result<String> { self.read_string_leb128_max(usize::MAX) }
+
+    /// Read string prefixed with length encoded as in [`SafeRead::read_leb128`], failing if
+    /// it's longer than `max` bytes. Streams that can't tell how much data is left are read
+    /// incrementally, so a bogus length can't make it allocate more than is actually there.
+    ///
+    /// ```
+    /// use pakr_typed_io::*;
+    ///
+    /// fn main() -> Result<(), eyre::Report> {
+    ///     let data = [0x81; 9].iter().chain(&[0x00, b'a']).copied().collect::<Vec<_>>();
+    ///
+    ///     let mut rd = CountingReader::new(&data[..]);
+    ///     assert!(rd.read_string_leb128().is_err());
+    ///
+    ///     let mut rd = CountingReader::new(&data[..]);
+    ///     assert_eq!(
+    ///         rd.read_string_leb128_max(0x1000).unwrap_err().to_string(),
+    ///         "string at 0x0 claims 0x8102040810204080 bytes, more than 0x1000 allowed"
+    ///     );
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    fn read_string_leb128_max(&mut self, max: usize) -> Result<String> {
+        let start = At(self.offset());
+        let size = self.read_leb128()?;
+        read_sized_str(self, start, size, max)
+    }
+
+    /// Read `units` big-endian UTF-16 code units
+    fn read_utf16be(&mut self, units: usize) -> Result<String> {
+        let start = At(self.offset());
+        let buf = self.read_vec_u16be(units)?;
+        decode_utf16(&buf).wrap_err_with(|| format!("invalid UTF-16 string{start}"))
+    }
+
+    /// Read big-endian UTF-16 string prefixed with `u16` count of code units
+    fn read_utf16be_prefixed(&mut self) -> Result<String> {
+        let start = At(self.offset());
+        let units = self.read_u16be()?;
+        let buf = self.read_vec_u16be(units as usize)?;
+        decode_utf16(&buf).wrap_err_with(|| format!("invalid UTF-16 string{start}"))
+    }
+
+    /// Read zero-terminated big-endian UTF-16 string
+    fn read_utf16be_cstr(&mut self) -> Result<String> { self.read_utf16be_cstr_max(usize::MAX) }
+
+    /// Read zero-terminated big-endian UTF-16 string, at most `max` code units long (sans
+    /// terminator)
+    fn read_utf16be_cstr_max(&mut self, max: usize) -> Result<String> {
+        let start = At(self.offset());
+        let buf = read_utf16_until_zero(self, max, u16::from_be_bytes)
+            .wrap_err_with(|| format!("can't read string{start}"))?;
+        decode_utf16(&buf).wrap_err_with(|| format!("invalid UTF-16 string{start}"))
+    }
+
+    /// Read `units` little-endian UTF-16 code units
+    fn read_utf16le(&mut self, units: usize) -> Result<String> {
+        let start = At(self.offset());
+        let buf = self.read_vec_u16le(units)?;
+        decode_utf16(&buf).wrap_err_with(|| format!("invalid UTF-16 string{start}"))
+    }
+
+    /// Read little-endian UTF-16 string prefixed with `u16` count of code units
+    fn read_utf16le_prefixed(&mut self) -> Result<String> {
+        let start = At(self.offset());
+        let units = self.read_u16le()?;
+        let buf = self.read_vec_u16le(units as usize)?;
+        decode_utf16(&buf).wrap_err_with(|| format!("invalid UTF-16 string{start}"))
+    }
+
+    /// Read zero-terminated little-endian UTF-16 string
+    fn read_utf16le_cstr(&mut self) -> Result<String> { self.read_utf16le_cstr_max(usize::MAX) }
+
+    /// Read zero-terminated little-endian UTF-16 string, at most `max` code units long (sans
+    /// terminator)
+    fn read_utf16le_cstr_max(&mut self, max: usize) -> Result<String> {
+        let start = At(self.offset());
+        let buf = read_utf16_until_zero(self, max, u16::from_le_bytes)
+            .wrap_err_with(|| format!("can't read string{start}"))?;
+        decode_utf16(&buf).wrap_err_with(|| format!("invalid UTF-16 string{start}"))
+    }
+
+    /// Read `size` bytes of Modified UTF-8 (see [`crate::text::decode_mutf8`])
+    fn read_mutf8(&mut self, size: usize) -> Result<String> {
+        let start = At(self.offset());
+        let bytes = self.read_as_vec(size)?;
+        decode_mutf8(&bytes)
+            .wrap_err_with(|| format!("invalid Modified UTF-8 string{start}"))
+    }
+
+    /// Read `size` bytes of Latin-1
+    fn read_latin1(&mut self, size: usize) -> Result<String> {
+        let bytes = self.read_as_vec(size)?;
+        Ok(decode_latin1(&bytes))
+    }
+
     fn read_leb128(&mut self) -> Result<u128> {
         let mut res = 0_u128;
         let mut cnt = 0;
@@ -206,3 +792,96 @@ pub trait SafeRead: Read + Seek {
         Err(eyre!("read_vlq128 overflow - no end byte after {cnt} bits"))
     }
 }
+
+/// Positioning on seekable streams, shared by readers and writers
+#[allow(clippy::len_without_is_empty)]
+pub trait SafeSeek: Seek {
+    fn tell(&mut self) -> Result<u64> {
+        let pos = self.stream_position()?;
+        Ok(pos)
+    }
+
+    fn len(&mut self) -> Result<usize> {
+        let here = self.tell()?;
+        let end_pos = self.seek(SeekFrom::End(0))?;
+        self.seek(SeekFrom::Start(here))?;
+        Ok(end_pos as usize)
+    }
+
+    /// Read next byte without consuming it
+    fn peek_u8(&mut self) -> Result<u8>
+    where
+        Self: SafeRead,
+    {
+        let val = self.read_u8()?;
+        self.seek(SeekFrom::Current(-1))?;
+        Ok(val)
+    }
+}
+
+impl<T> SafeSeek for T where T: Seek + ?Sized {}
+
+/// Forward-only reader keeping track of its position
+///
+/// ```
+/// use pakr_typed_io::*;
+///
+/// use std::io::Read;
+///
+/// fn main() -> Result<(), eyre::Report> {
+///     // Chained readers can't seek
+///     let data = &b"\x12\x34"[..];
+///     let mut rd = CountingReader::new(data.chain(&b"abc\xff\0"[..]));
+///
+///     assert_eq!(0x1234, rd.read_u16be()?);
+///     assert_eq!(2, rd.position());
+///     assert_eq!(
+///         rd.read_cstr_utf8().unwrap_err().to_string(),
+///         "invalid UTF-8 in string at 0x2: invalid utf-8 sequence of 1 bytes from index 3"
+///     );
+///     assert_eq!(7, rd.position());
+///
+///     Ok(())
+/// }
+/// ```
+pub struct CountingReader<R> {
+    inner: R,
+    pos:   u64,
+}
+
+impl<R> CountingReader<R>
+where
+    R: Read,
+{
+    pub fn new(inner: R) -> Self { Self { inner, pos: 0 } }
+
+    /// Start counting from `pos` instead of zero, e.g. if some data was already consumed
+    pub fn with_position(inner: R, pos: u64) -> Self { Self { inner, pos } }
+
+    /// Count of bytes read so far
+    pub fn position(&self) -> u64 { self.pos }
+
+    pub fn get_ref(&self) -> &R { &self.inner }
+
+    pub fn get_mut(&mut self) -> &mut R { &mut self.inner }
+
+    pub fn into_inner(self) -> R { self.inner }
+}
+
+impl<R> Read for CountingReader<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let got = self.inner.read(buf)?;
+        self.pos += got as u64;
+        Ok(got)
+    }
+}
+
+impl<R> SafeRead for CountingReader<R>
+where
+    R: Read,
+{
+    fn offset(&mut self) -> Option<u64> { Some(self.pos) }
+}
@@ -0,0 +1,201 @@
+//! Readers and writers over plain byte slices
+//!
+//! [`SliceReader`] and [`SliceWriter`] don't own or grow their buffer, so they suit fixed
+//! packet buffers and firmware images. Writing past end of buffer is an error, never a
+//! reallocation.
+//!
+//! They still need `std`: the crate doesn't support `#![no_std]`. Every method returns
+//! [`eyre::Result`] and eyre requires `std`, and the traits are built on [`std::io::Read`] and
+//! [`std::io::Write`]. Going without would mean a new error type across the whole public API.
+//!
+//! ```
+//! use pakr_typed_io::*;
+//! use pakr_typed_io::be::*;
+//! use pakr_typed_io::slice::{SliceReader, SliceWriter};
+//!
+//! fn main() -> Result<(), eyre::Report> {
+//!     let mut packet = [0u8; 8];
+//!     let mut wr = SliceWriter::new(&mut packet);
+//!     wr.write_auto((0xCAFE_u16, 7_u8))?;
+//!     wr.write_leb128(300)?;
+//!     assert_eq!(b"\xca\xfe\x07\x82\x2c", wr.written());
+//!
+//!     // Buffer is full
+//!     assert!(wr.write_u32_be(0).is_err());
+//!
+//!     let mut rd = SliceReader::new(&packet);
+//!     let (magic, ver): (u16, u8) = rd.read_auto()?;
+//!     assert_eq!((0xCAFE, 7, 300), (magic, ver, rd.read_leb128()?));
+//!     assert_eq!(3, rd.remaining().len());
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! [`SliceReader`] can also hand out borrows of its input instead of copies, living as long as
+//! the buffer itself.
+//!
+//! ```
+//! use pakr_typed_io::*;
+//! use pakr_typed_io::slice::SliceReader;
+//!
+//! fn main() -> Result<(), eyre::Report> {
+//!     let buf = b"\x04dataname\0\xff\xfe".to_vec();
+//!
+//!     let (tag, name) = {
+//!         let mut rd = SliceReader::new(&buf);
+//!         let size = rd.read_u8()?;
+//!         let tag = rd.read_str_utf8(size as usize)?;
+//!         let name = rd.read_cstr()?;
+//!
+//!         // Failed reads don't consume anything
+//!         assert_eq!(
+//!             rd.read_str_utf8(2).unwrap_err().to_string(),
+//!             "invalid UTF-8 in string at 0xA: invalid utf-8 sequence of 1 bytes from index 0"
+//!         );
+//!         assert!(rd.read_bytes(3).is_err());
+//!         assert_eq!(b"\xff\xfe", rd.read_bytes(2)?);
+//!         (tag, name)
+//!     };
+//!
+//!     // Borrows outlive the reader
+//!     assert_eq!("data", tag);
+//!     assert_eq!(c"name", name);
+//!     Ok(())
+//! }
+//! ```
+
+use std::{
+    ffi::CStr,
+    io::{ErrorKind, Read, Seek, SeekFrom, Write},
+};
+
+use eyre::{eyre, Result};
+
+use crate::SafeRead;
+
+pub(crate) fn seek_in(len: usize, pos: usize, to: SeekFrom) -> std::io::Result<usize> {
+    let (base, delta) = match to {
+        SeekFrom::Start(v) => {
+            return usize::try_from(v).map_err(|_| ErrorKind::InvalidInput.into())
+        },
+        SeekFrom::End(v) => (len, v),
+        SeekFrom::Current(v) => (pos, v),
+    };
+    base.checked_add_signed(delta as isize)
+        .ok_or_else(|| std::io::Error::new(ErrorKind::InvalidInput, "seek to negative position"))
+}
+
+/// Reader over borrowed bytes
+#[derive(Clone, Debug)]
+pub struct SliceReader<'a> {
+    data: &'a [u8],
+    pos:  usize,
+}
+
+impl<'a> SliceReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self { Self { data, pos: 0 } }
+
+    pub fn position(&self) -> usize { self.pos }
+
+    /// Whole underlying buffer
+    pub fn get_ref(&self) -> &'a [u8] { self.data }
+
+    /// Part of buffer not read yet
+    pub fn remaining(&self) -> &'a [u8] { &self.data[self.pos.min(self.data.len()) ..] }
+
+    /// Borrow next `size` bytes without copying
+    pub fn read_bytes(&mut self, size: usize) -> Result<&'a [u8]> {
+        let left = self.remaining();
+        if size > left.len() {
+            return Err(eyre!(
+                "can't read 0x{size:X} bytes at 0x{:X}, only 0x{:X} left",
+                self.pos,
+                left.len()
+            ));
+        }
+        self.pos += size;
+        Ok(&left[.. size])
+    }
+
+    /// Borrow zero-terminated string, consuming terminator
+    pub fn read_cstr(&mut self) -> Result<&'a CStr> {
+        let val = CStr::from_bytes_until_nul(self.remaining())
+            .map_err(|_| eyre!("can't read string at 0x{:X}", self.pos))?;
+        self.pos += val.to_bytes_with_nul().len();
+        Ok(val)
+    }
+
+    /// Borrow next `size` bytes as string, failing on invalid UTF-8
+    pub fn read_str_utf8(&mut self, size: usize) -> Result<&'a str> {
+        let start = self.pos;
+        let bytes = self.read_bytes(size)?;
+        std::str::from_utf8(bytes).map_err(|e| {
+            self.pos = start;
+            eyre!("invalid UTF-8 in string at 0x{start:X}: {e}")
+        })
+    }
+}
+
+impl Read for SliceReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let src = self.remaining();
+        let got = src.len().min(buf.len());
+        buf[.. got].copy_from_slice(&src[.. got]);
+        self.pos += got;
+        Ok(got)
+    }
+}
+
+impl Seek for SliceReader<'_> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.pos = seek_in(self.data.len(), self.pos, pos)?;
+        Ok(self.pos as u64)
+    }
+}
+
+impl SafeRead for SliceReader<'_> {
+    fn as_seek(&mut self) -> Option<&mut dyn Seek> { Some(self) }
+
+    fn offset(&mut self) -> Option<u64> { Some(self.pos as u64) }
+}
+
+/// Writer into borrowed fixed-size buffer
+#[derive(Debug)]
+pub struct SliceWriter<'a> {
+    data: &'a mut [u8],
+    pos:  usize,
+    /// End of written data, unaffected by seeking back
+    end:  usize,
+}
+
+impl<'a> SliceWriter<'a> {
+    pub fn new(data: &'a mut [u8]) -> Self { Self { data, pos: 0, end: 0 } }
+
+    pub fn position(&self) -> usize { self.pos }
+
+    /// Data written so far, up to furthest position written
+    pub fn written(&self) -> &[u8] { &self.data[.. self.end] }
+
+    pub fn into_inner(self) -> &'a mut [u8] { self.data }
+}
+
+impl Write for SliceWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let start = self.pos.min(self.data.len());
+        let put = (self.data.len() - start).min(buf.len());
+        self.data[start .. start + put].copy_from_slice(&buf[.. put]);
+        self.pos = start + put;
+        self.end = self.end.max(self.pos);
+        Ok(put)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> { Ok(()) }
+}
+
+impl Seek for SliceWriter<'_> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.pos = seek_in(self.data.len(), self.pos, pos)?;
+        Ok(self.pos as u64)
+    }
+}
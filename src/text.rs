@@ -0,0 +1,153 @@
+//! Text encodings other than UTF-8
+//!
+//! Plain conversion helpers, used by string methods of [`crate::SafeRead`] and
+//! [`crate::SafeWrite`], but usable on their own for already read buffers.
+//!
+//! ```
+//! use pakr_typed_io::*;
+//!
+//! use std::io::Cursor;
+//!
+//! fn main() -> Result<(), eyre::Report> {
+//!     let mut buf = vec![];
+//!     {
+//!         let mut cur = Cursor::new(&mut buf);
+//!         cur.write_utf16_le_cstr("Ωmega 😀")?;
+//!         cur.write_utf16_be_prefixed("BE")?;
+//!         cur.write_mutf8("a\0😀")?;
+//!         cur.write_latin1("café")?;
+//!
+//!         // Latin-1 covers U+0000 to U+00FF only
+//!         assert!(cur.write_latin1("Ω").is_err());
+//!     }
+//!
+//!     let mut cur = Cursor::new(&buf);
+//!     assert_eq!("Ωmega 😀", cur.read_utf16le_cstr()?);
+//!     assert_eq!("BE", cur.read_utf16be_prefixed()?);
+//!     assert_eq!("a\0😀", cur.read_mutf8(9)?);
+//!     assert_eq!("café", cur.read_latin1(4)?);
+//!
+//!     // Unpaired surrogates are rejected
+//!     let mut cur = Cursor::new(&[0x3D, 0xD8, 0x41, 0x00][..]);
+//!     assert!(cur.read_utf16le(2).is_err());
+//!
+//!     // Terminated strings can be bounded, as with C strings
+//!     let mut cur = Cursor::new(&b"l\0o\0n\0g\0\0\0"[..]);
+//!     assert_eq!(
+//!         format!("{:#}", cur.read_utf16le_cstr_max(3).unwrap_err()),
+//!         "can't read string at 0x0: UTF-16 string overflow - no terminator within 3 units"
+//!     );
+//!     assert_eq!("long", cur.read_utf16le_cstr_max(4)?);
+//!
+//!     // Modified UTF-8 forbids raw zero bytes and overlong forms, other than `C0 80`
+//!     assert!(pakr_typed_io::text::decode_mutf8(b"a\0").is_err());
+//!     assert!(pakr_typed_io::text::decode_mutf8(b"\xc1\x81").is_err());
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use eyre::{eyre, Result};
+
+/// Decode UTF-16 code units, failing on unpaired surrogates
+pub fn decode_utf16(units: &[u16]) -> Result<String> {
+    let mut res = String::with_capacity(units.len());
+    for (pos, chr) in char::decode_utf16(units.iter().copied()).enumerate() {
+        match chr {
+            Ok(chr) => res.push(chr),
+            Err(e) => {
+                return Err(eyre!(
+                    "unpaired surrogate 0x{:X} after {pos} characters",
+                    e.unpaired_surrogate()
+                ))
+            },
+        }
+    }
+    Ok(res)
+}
+
+/// Decode Modified UTF-8, as used by Java class files and `DataInput`
+///
+/// Zero is encoded as `C0 80` and supplementary characters as surrogate pairs, each in its
+/// own 3-byte sequence. Surrogates must be correctly paired. Decoding is strict: raw zero
+/// bytes and overlong sequences (other than `C0 80`) are rejected.
+pub fn decode_mutf8(bytes: &[u8]) -> Result<String> {
+    let mut units = Vec::with_capacity(bytes.len());
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let b0 = bytes[pos] as u16;
+        let (unit, size) = match b0 >> 4 {
+            0x0 ..= 0x7 if b0 != 0 => (b0, 1),
+            0xC | 0xD => {
+                let b1 = mutf8_cont(bytes, pos, 1)?;
+                ((b0 & 0x1F) << 6 | b1, 2)
+            },
+            0xE => {
+                let b1 = mutf8_cont(bytes, pos, 1)?;
+                let b2 = mutf8_cont(bytes, pos, 2)?;
+                ((b0 & 0x0F) << 12 | b1 << 6 | b2, 3)
+            },
+            _ => return Err(eyre!("invalid Modified UTF-8 byte 0x{b0:X} at 0x{pos:X}")),
+        };
+        let overlong = match size {
+            2 => unit != 0 && unit < 0x80,
+            3 => unit < 0x800,
+            _ => false,
+        };
+        if overlong {
+            return Err(eyre!("overlong Modified UTF-8 sequence at 0x{pos:X}"));
+        }
+        units.push(unit);
+        pos += size;
+    }
+    decode_utf16(&units)
+}
+
+fn mutf8_cont(bytes: &[u8], pos: usize, idx: usize) -> Result<u16> {
+    match bytes.get(pos + idx) {
+        Some(&v) if v & 0xC0 == 0x80 => Ok((v & 0x3F) as u16),
+        Some(&v) => Err(eyre!(
+            "invalid Modified UTF-8 continuation byte 0x{v:X} at 0x{:X}",
+            pos + idx
+        )),
+        None => Err(eyre!("truncated Modified UTF-8 sequence at 0x{pos:X}")),
+    }
+}
+
+/// Encode as Modified UTF-8
+pub fn encode_mutf8(val: &str) -> Vec<u8> {
+    let mut res = Vec::with_capacity(val.len());
+    for unit in val.encode_utf16() {
+        match unit {
+            0x0001 ..= 0x007F => res.push(unit as u8),
+            0x0000 | 0x0080 ..= 0x07FF => {
+                res.push(0xC0 | (unit >> 6) as u8);
+                res.push(0x80 | (unit & 0x3F) as u8);
+            },
+            _ => {
+                res.push(0xE0 | (unit >> 12) as u8);
+                res.push(0x80 | (unit >> 6 & 0x3F) as u8);
+                res.push(0x80 | (unit & 0x3F) as u8);
+            },
+        }
+    }
+    res
+}
+
+/// Decode ISO-8859-1, which maps every byte to a character
+pub fn decode_latin1(bytes: &[u8]) -> String { bytes.iter().map(|&v| v as char).collect() }
+
+/// Encode as ISO-8859-1, failing on characters above U+00FF
+pub fn encode_latin1(val: &str) -> Result<Vec<u8>> {
+    val.chars()
+        .enumerate()
+        .map(|(pos, chr)| {
+            u8::try_from(chr).map_err(|_| {
+                eyre!(
+                    "character U+{:04X} at {pos} not representable in Latin-1",
+                    chr as u32
+                )
+            })
+        })
+        .collect()
+}
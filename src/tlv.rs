@@ -1,4 +1,4 @@
-use crate::{SafeRead, SafeWrite};
+use crate::{endian::Endian, SafeRead, SafeWrite};
 use eyre::Result;
 
 /// TLV field order
@@ -11,10 +11,8 @@ pub enum Fields {
     None,
 }
 
-pub enum Endianess {
-    Big,
-    Little,
-}
+/// Kept for compatibility, use [`Endian`] instead
+pub type Endianess = Endian;
 
 pub struct TlvType {
     /// Order of fields. If not all fields are present, fill with Fields::None
@@ -46,13 +44,15 @@ pub const TLV_PNG: TlvType = TlvType {
     csum_includes_length: false,
 };
 
+// Fields are unused until `read` and `write` get implemented
+#[allow(dead_code)]
 pub struct Chunk {
     typ:      [u8; 4],
     checksum: Option<u32>,
     data:     Vec<u8>,
 }
 
-pub fn read<SR>(src: &mut SR, typ: &TlvType) -> Result<Chunk>
+pub fn read<SR>(_src: &mut SR, _typ: &TlvType) -> Result<Chunk>
 where
     SR: SafeRead,
 {
@@ -60,7 +60,7 @@ where
 }
 
 impl Chunk {
-    pub fn write<SW>(&self, dst: &mut SW, typ: &TlvType) -> Result<()>
+    pub fn write<SW>(&self, _dst: &mut SW, _typ: &TlvType) -> Result<()>
     where
         SW: SafeWrite,
     {
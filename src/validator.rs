@@ -259,3 +259,262 @@ where
 {
     fn validate(self) -> Result<Self> { Ok(self) }
 }
+
+/// Prefix of value in failure message, empty for no context
+fn label(msg: &str) -> String {
+    if msg.is_empty() {
+        String::new()
+    } else {
+        format!("{msg}=")
+    }
+}
+
+/// Validators for floating-point values, printed in decimal
+pub trait FloatValidator: Sized {
+    fn validate_finite(self) -> Result<Self> { self.validate_finite_ctx("") }
+
+    fn validate_not_nan(self) -> Result<Self> { self.validate_not_nan_ctx("") }
+
+    fn validate_in_range<R>(self, range: R) -> Result<Self>
+    where
+        R: std::ops::RangeBounds<Self> + fmt::Debug,
+    {
+        self.validate_in_range_ctx(range, "")
+    }
+
+    /// Fail if value differs from `val` by more than `tolerance`
+    fn validate_approx_eq(self, val: Self, tolerance: Self) -> Result<Self> {
+        self.validate_approx_eq_ctx(val, tolerance, "")
+    }
+
+    fn validate_finite_ctx(self, msg: &str) -> Result<Self>;
+
+    fn validate_not_nan_ctx(self, msg: &str) -> Result<Self>;
+
+    fn validate_in_range_ctx<R>(self, range: R, msg: &str) -> Result<Self>
+    where
+        R: std::ops::RangeBounds<Self> + fmt::Debug;
+
+    fn validate_approx_eq_ctx(self, val: Self, tolerance: Self, msg: &str) -> Result<Self>;
+}
+
+macro_rules! float_validator {
+    ($($typ:ty),+) => {
+        $(
+            impl FloatValidator for $typ {
+                fn validate_finite_ctx(self, msg: &str) -> Result<Self> {
+                    if self.is_finite() {
+                        Ok(self)
+                    } else {
+                        Err(eyre!("value {}{:?} is not finite", label(msg), self))
+                    }
+                }
+
+                fn validate_not_nan_ctx(self, msg: &str) -> Result<Self> {
+                    if !self.is_nan() {
+                        Ok(self)
+                    } else {
+                        Err(eyre!("value {}NaN is not allowed", label(msg)))
+                    }
+                }
+
+                fn validate_in_range_ctx<R>(self, range: R, msg: &str) -> Result<Self>
+                where
+                    R: std::ops::RangeBounds<Self> + fmt::Debug,
+                {
+                    if range.contains(&self) {
+                        Ok(self)
+                    } else {
+                        Err(eyre!(
+                            "value {}{:?} not in expected range {:?}",
+                            label(msg),
+                            self,
+                            range
+                        ))
+                    }
+                }
+
+                fn validate_approx_eq_ctx(
+                    self,
+                    val: Self,
+                    tolerance: Self,
+                    msg: &str,
+                ) -> Result<Self> {
+                    // NaN never compares, so it always fails. Equal infinities have no
+                    // finite difference, so are compared directly.
+                    if self == val || (self - val).abs() <= tolerance {
+                        Ok(self)
+                    } else {
+                        Err(eyre!(
+                            "value {}{:?} not within {:?} of expected {:?}",
+                            label(msg),
+                            self,
+                            tolerance,
+                            val
+                        ))
+                    }
+                }
+            }
+        )+
+    };
+}
+
+float_validator!(f32, f64);
+
+/// Validators for `bool`
+pub trait BoolValidator: Sized {
+    fn validate_equals(self, val: bool) -> Result<Self> { self.validate_equals_ctx(val, "") }
+
+    fn validate_equals_ctx(self, val: bool, msg: &str) -> Result<Self>;
+}
+
+impl BoolValidator for bool {
+    fn validate_equals_ctx(self, val: bool, msg: &str) -> Result<Self> {
+        if self == val {
+            Ok(self)
+        } else {
+            Err(eyre!(
+                "value {}{self} not equal to expected {val}",
+                label(msg)
+            ))
+        }
+    }
+}
+
+/// Validators for `char`, printed as Unicode code points
+pub trait CharValidator: Sized {
+    fn validate_equals(self, val: char) -> Result<Self> { self.validate_equals_ctx(val, "") }
+
+    fn validate_in_range<R>(self, range: R) -> Result<Self>
+    where
+        R: std::ops::RangeBounds<char> + fmt::Debug,
+    {
+        self.validate_in_range_ctx(range, "")
+    }
+
+    fn validate_in_list(self, list: &[char]) -> Result<Self> {
+        self.validate_in_list_ctx(list, "")
+    }
+
+    fn validate_equals_ctx(self, val: char, msg: &str) -> Result<Self>;
+
+    fn validate_in_range_ctx<R>(self, range: R, msg: &str) -> Result<Self>
+    where
+        R: std::ops::RangeBounds<char> + fmt::Debug;
+
+    fn validate_in_list_ctx(self, list: &[char], msg: &str) -> Result<Self>;
+}
+
+impl CharValidator for char {
+    fn validate_equals_ctx(self, val: char, msg: &str) -> Result<Self> {
+        if self == val {
+            Ok(self)
+        } else {
+            Err(eyre!(
+                "value {}U+{:04X} not equal to expected U+{:04X}",
+                label(msg),
+                self as u32,
+                val as u32
+            ))
+        }
+    }
+
+    fn validate_in_range_ctx<R>(self, range: R, msg: &str) -> Result<Self>
+    where
+        R: std::ops::RangeBounds<char> + fmt::Debug,
+    {
+        if range.contains(&self) {
+            Ok(self)
+        } else {
+            Err(eyre!(
+                "value {}U+{:04X} not in expected range {:?}",
+                label(msg),
+                self as u32,
+                range
+            ))
+        }
+    }
+
+    fn validate_in_list_ctx(self, list: &[char], msg: &str) -> Result<Self> {
+        if list.contains(&self) {
+            Ok(self)
+        } else {
+            Err(eyre!(
+                "value {}U+{:04X} is not on expected list {:?}",
+                label(msg),
+                self as u32,
+                list
+            ))
+        }
+    }
+}
+
+/// Validators for byte strings, like magic numbers and reserved areas
+pub trait BytesValidator: Sized + AsRef<[u8]> {
+    fn validate_equals(self, val: &[u8]) -> Result<Self> { self.validate_equals_ctx(val, "") }
+
+    fn validate_starts_with(self, prefix: &[u8]) -> Result<Self> {
+        self.validate_starts_with_ctx(prefix, "")
+    }
+
+    fn validate_all_zero(self) -> Result<Self> { self.validate_all_zero_ctx("") }
+
+    fn validate_len(self, len: usize) -> Result<Self> { self.validate_len_ctx(len, "") }
+
+    fn validate_equals_ctx(self, val: &[u8], msg: &str) -> Result<Self> {
+        if self.as_ref() == val {
+            Ok(self)
+        } else {
+            Err(eyre!(
+                "value {}{:02X?} not equal to expected {:02X?}",
+                label(msg),
+                self.as_ref(),
+                val
+            ))
+        }
+    }
+
+    fn validate_starts_with_ctx(self, prefix: &[u8], msg: &str) -> Result<Self> {
+        if self.as_ref().starts_with(prefix) {
+            Ok(self)
+        } else {
+            Err(eyre!(
+                "value {}{:02X?} doesn't start with expected {:02X?}",
+                label(msg),
+                self.as_ref(),
+                prefix
+            ))
+        }
+    }
+
+    fn validate_all_zero_ctx(self, msg: &str) -> Result<Self> {
+        match self.as_ref().iter().position(|&v| v != 0) {
+            None => Ok(self),
+            // Reserved areas can be large, so only offending byte is shown
+            Some(pos) => Err(eyre!(
+                "value {}0x{:X} at offset 0x{pos:X} is not zero",
+                label(msg),
+                self.as_ref()[pos]
+            )),
+        }
+    }
+
+    fn validate_len_ctx(self, len: usize, msg: &str) -> Result<Self> {
+        let actual = self.as_ref().len();
+        if actual == len {
+            Ok(self)
+        } else {
+            Err(eyre!(
+                "value {}{:02X?} length 0x{actual:X} not equal to expected 0x{len:X}",
+                label(msg),
+                self.as_ref()
+            ))
+        }
+    }
+}
+
+impl BytesValidator for &[u8] {}
+
+impl BytesValidator for Vec<u8> {}
+
+impl<const N: usize> BytesValidator for [u8; N] {}
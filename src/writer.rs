@@ -1,26 +1,207 @@
 use std::{
-    fs::File,
-    io::{Cursor, Seek, Write},
+    collections::BTreeSet,
+    io::{Seek, SeekFrom, Write},
+    marker::PhantomData,
+    sync::{Arc, Mutex},
 };
 
-use eyre::Result;
+use eyre::{eyre, Result};
 
-impl SafeWrite for Cursor<Vec<u8>> {}
+use crate::{
+    be,
+    endian::Endian,
+    le,
+    text::{encode_latin1, encode_mutf8},
+    Padding, SafeSeek,
+};
 
-impl SafeWrite for Cursor<&mut Vec<u8>> {}
+/// Size of intermediate buffer used by bulk writes
+const BULK_CHUNK: usize = 4096;
 
-impl SafeWrite for Cursor<&mut [u8]> {}
+macro_rules! bulk_write {
+    ($name:ident, $typ:ty, $conv:ident) => {
+        /// Write all values from `val`, encoding one buffer at a time
+        fn $name(&mut self, val: &[$typ]) -> Result<()> {
+            const SIZE: usize = std::mem::size_of::<$typ>();
+            let mut buf = [0u8; BULK_CHUNK];
+            for part in val.chunks(BULK_CHUNK / SIZE) {
+                for (v, raw) in part.iter().zip(buf.chunks_exact_mut(SIZE)) {
+                    raw.copy_from_slice(&v.$conv());
+                }
+                self.write_all(&buf[.. part.len() * SIZE])?;
+            }
+            Ok(())
+        }
+    };
+}
 
-impl SafeWrite for &mut File {}
+macro_rules! reserve {
+    ($name:ident, $typ:ty, $endian:expr) => {
+        /// Reserve space for value to be filled later with [`SafeWrite::fill`]
+        fn $name(&mut self) -> Result<Placeholder<$typ>>
+        where
+            Self: Sized,
+        {
+            self.reserve($endian)
+        }
+    };
+}
 
-impl SafeWrite for File {}
+/// Position of a value reserved in output stream, to be filled with [`SafeWrite::fill`]
+///
+/// Placeholders registered with [`Patches::track`] are checked by [`Patches::finish`].
+#[must_use = "placeholder must be filled"]
+pub struct Placeholder<T> {
+    pos:     u64,
+    endian:  Endian,
+    tracker: Option<Patches>,
+    typ:     PhantomData<T>,
+}
 
-pub trait SafeWrite: Write + Seek {
-    fn tell(&mut self) -> Result<u64> {
-        let pos = self.stream_position()?;
-        Ok(pos)
+impl<T> Placeholder<T> {
+    /// Position of reserved value in stream
+    pub fn position(&self) -> u64 { self.pos }
+
+    /// Abandon placeholder, leaving reserved space zeroed
+    pub fn cancel(self) { self.settle(); }
+
+    fn settle(&self) {
+        if let Some(tracker) = &self.tracker {
+            tracker.open.lock().unwrap_or_else(|e| e.into_inner()).remove(&self.pos);
+        }
+    }
+}
+
+/// Register of placeholders, to check none was left unfilled before output is complete
+///
+/// ```
+/// use pakr_typed_io::*;
+///
+/// use std::io::Cursor;
+///
+/// fn main() -> Result<(), eyre::Report> {
+///     let mut buf = vec![];
+///     let mut cur = Cursor::new(&mut buf);
+///     let patches = Patches::default();
+///
+///     let size = patches.track(cur.reserve_u16_be()?);
+///     let count = patches.track(cur.reserve_u8()?);
+///     cur.write_exact(b"data")?;
+///     cur.fill(size, 4)?;
+///     drop(count);
+///
+///     assert_eq!(
+///         patches.finish().unwrap_err().to_string(),
+///         "placeholder at 0x2 was never filled"
+///     );
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Patches {
+    open: Arc<Mutex<BTreeSet<u64>>>,
+}
+
+impl Patches {
+    /// Register `placeholder` as one that must be filled or cancelled
+    pub fn track<T>(&self, mut placeholder: Placeholder<T>) -> Placeholder<T> {
+        self.open.lock().unwrap_or_else(|e| e.into_inner()).insert(placeholder.pos);
+        placeholder.tracker = Some(self.clone());
+        placeholder
+    }
+
+    /// Fail if any tracked placeholder was neither filled nor cancelled
+    pub fn finish(&self) -> Result<()> {
+        let open = self.open.lock().unwrap_or_else(|e| e.into_inner());
+        match open.iter().next() {
+            None => Ok(()),
+            Some(pos) => Err(eyre!("placeholder at 0x{pos:X} was never filled")),
+        }
+    }
+}
+
+/// Write by `body` at `pos`, then return to current position, even if writing failed
+fn patch_at<SW, F>(dst: &mut SW, pos: u64, body: F) -> Result<()>
+where
+    SW: SafeWrite + ?Sized,
+    F: FnOnce(&mut SW) -> Result<()>,
+{
+    let here = dst.tell()?;
+    dst.seek(SeekFrom::Start(pos))?;
+    let res = body(dst);
+    dst.seek(SeekFrom::Start(here))?;
+    res
+}
+
+/// Format of length field written by [`SafeWrite::with_length_prefix`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LenFmt {
+    U8,
+    U16Be,
+    U16Le,
+    U32Be,
+    U32Le,
+    U64Be,
+    U64Le,
+}
+
+impl LenFmt {
+    /// Size of length field in bytes
+    pub fn size(self) -> u64 {
+        match self {
+            LenFmt::U8 => 1,
+            LenFmt::U16Be | LenFmt::U16Le => 2,
+            LenFmt::U32Be | LenFmt::U32Le => 4,
+            LenFmt::U64Be | LenFmt::U64Le => 8,
+        }
     }
 
+    /// Write `len` in this format, failing if it doesn't fit
+    pub fn write<SW>(self, dst: &mut SW, len: u64) -> Result<()>
+    where
+        SW: SafeWrite + ?Sized,
+    {
+        let overflow = |_| eyre!("length 0x{len:X} doesn't fit in {self:?} field");
+        match self {
+            LenFmt::U8 => dst.write_u8(len.try_into().map_err(overflow)?),
+            LenFmt::U16Be => dst.write_u16_be(len.try_into().map_err(overflow)?),
+            LenFmt::U16Le => dst.write_u16_le(len.try_into().map_err(overflow)?),
+            LenFmt::U32Be => dst.write_u32_be(len.try_into().map_err(overflow)?),
+            LenFmt::U32Le => dst.write_u32_le(len.try_into().map_err(overflow)?),
+            LenFmt::U64Be => dst.write_u64_be(len),
+            LenFmt::U64Le => dst.write_u64_le(len),
+        }
+    }
+}
+
+fn length_prefixed<SW, R, F>(dst: &mut SW, fmt: LenFmt, inclusive: bool, body: F) -> Result<R>
+where
+    SW: SafeWrite,
+    F: FnOnce(&mut SW) -> Result<R>,
+{
+    let start = dst.tell()?;
+    fmt.write(dst, 0)?;
+    let res = body(dst)?;
+    let end = dst.tell()?;
+
+    let from = if inclusive { start } else { start + fmt.size() };
+    let len = end.checked_sub(from).ok_or_else(|| {
+        eyre!("length-prefixed section at 0x{start:X} ends at 0x{end:X}, before it begins")
+    })?;
+    patch_at(dst, start, |dst| fmt.write(dst, len))?;
+    Ok(res)
+}
+
+fn prefix_overflow(val: &str, prefix: &str) -> eyre::Report {
+    eyre!(
+        "string of 0x{:X} bytes too long for {prefix} length prefix",
+        val.len()
+    )
+}
+
+impl<T> SafeWrite for T where T: Write + Seek + ?Sized {}
+
+pub trait SafeWrite: Write + Seek {
     fn write_i8(&mut self, val: i8) -> Result<()> {
         let bytes = val.to_be_bytes();
         self.write_all(&bytes)?;
@@ -112,8 +293,244 @@ pub trait SafeWrite: Write + Seek {
         Ok(())
     }
 
+    bulk_write!(write_u16_be_slice, u16, to_be_bytes);
+    bulk_write!(write_u16_le_slice, u16, to_le_bytes);
+    bulk_write!(write_i16_be_slice, i16, to_be_bytes);
+    bulk_write!(write_i16_le_slice, i16, to_le_bytes);
+    bulk_write!(write_u32_be_slice, u32, to_be_bytes);
+    bulk_write!(write_u32_le_slice, u32, to_le_bytes);
+    bulk_write!(write_i32_be_slice, i32, to_be_bytes);
+    bulk_write!(write_i32_le_slice, i32, to_le_bytes);
+    bulk_write!(write_u64_be_slice, u64, to_be_bytes);
+    bulk_write!(write_u64_le_slice, u64, to_le_bytes);
+    bulk_write!(write_i64_be_slice, i64, to_be_bytes);
+    bulk_write!(write_i64_le_slice, i64, to_le_bytes);
+    bulk_write!(write_u128_be_slice, u128, to_be_bytes);
+    bulk_write!(write_u128_le_slice, u128, to_le_bytes);
+    bulk_write!(write_i128_be_slice, i128, to_be_bytes);
+    bulk_write!(write_i128_le_slice, i128, to_le_bytes);
+
     fn write_exact(&mut self, val: &[u8]) -> Result<()> {
         self.write_all(val)?;
         Ok(())
     }
+
+    /// Write zeroed value of type `T` and return placeholder to fill it later
+    fn reserve<T>(&mut self, endian: Endian) -> Result<Placeholder<T>>
+    where
+        Self: Sized + be::SafeWriter<T> + le::SafeWriter<T>,
+        T: Default,
+    {
+        let pos = self.tell()?;
+        match endian {
+            Endian::Big => be::SafeWriter::<T>::write_auto(self, T::default())?,
+            Endian::Little => le::SafeWriter::<T>::write_auto(self, T::default())?,
+        }
+        Ok(Placeholder {
+            pos,
+            endian,
+            tracker: None,
+            typ: PhantomData,
+        })
+    }
+
+    reserve!(reserve_u8, u8, Endian::Big);
+    reserve!(reserve_i8, i8, Endian::Big);
+    reserve!(reserve_u16_be, u16, Endian::Big);
+    reserve!(reserve_u16_le, u16, Endian::Little);
+    reserve!(reserve_i16_be, i16, Endian::Big);
+    reserve!(reserve_i16_le, i16, Endian::Little);
+    reserve!(reserve_u32_be, u32, Endian::Big);
+    reserve!(reserve_u32_le, u32, Endian::Little);
+    reserve!(reserve_i32_be, i32, Endian::Big);
+    reserve!(reserve_i32_le, i32, Endian::Little);
+    reserve!(reserve_u64_be, u64, Endian::Big);
+    reserve!(reserve_u64_le, u64, Endian::Little);
+    reserve!(reserve_i64_be, i64, Endian::Big);
+    reserve!(reserve_i64_le, i64, Endian::Little);
+    reserve!(reserve_u128_be, u128, Endian::Big);
+    reserve!(reserve_u128_le, u128, Endian::Little);
+    reserve!(reserve_i128_be, i128, Endian::Big);
+    reserve!(reserve_i128_le, i128, Endian::Little);
+
+    /// Write `val` at position saved in `placeholder`, then return to current position
+    fn fill<T>(&mut self, placeholder: Placeholder<T>, val: T) -> Result<()>
+    where
+        Self: Sized + be::SafeWriter<T> + le::SafeWriter<T>,
+    {
+        patch_at(self, placeholder.pos, |dst| match placeholder.endian {
+            Endian::Big => be::SafeWriter::<T>::write_auto(dst, val),
+            Endian::Little => le::SafeWriter::<T>::write_auto(dst, val),
+        })?;
+        placeholder.settle();
+        Ok(())
+    }
+
+    /// Write length field in format `fmt`, followed by whatever `body` writes. Length field
+    /// is then filled with number of bytes written by `body`.
+    fn with_length_prefix<R, F>(&mut self, fmt: LenFmt, body: F) -> Result<R>
+    where
+        Self: Sized,
+        F: FnOnce(&mut Self) -> Result<R>,
+    {
+        length_prefixed(self, fmt, false, body)
+    }
+
+    /// Same as [`SafeWrite::with_length_prefix`], but length covers the length field too
+    fn with_length_prefix_inclusive<R, F>(&mut self, fmt: LenFmt, body: F) -> Result<R>
+    where
+        Self: Sized,
+        F: FnOnce(&mut Self) -> Result<R>,
+    {
+        length_prefixed(self, fmt, true, body)
+    }
+
+    /// Write zero-terminated string, failing if it contains a zero byte
+    fn write_cstr(&mut self, val: &str) -> Result<()> {
+        if let Some(pos) = val.bytes().position(|v| v == 0) {
+            return Err(eyre!("string has embedded zero at offset 0x{pos:X}"));
+        }
+        self.write_all(val.as_bytes())?;
+        self.write_u8(0)
+    }
+
+    /// Write string into fixed-width field of `size` bytes, filling the rest with `padding`
+    fn write_fixed_str(&mut self, val: &str, size: usize, padding: Padding) -> Result<()> {
+        if val.len() > size {
+            return Err(eyre!(
+                "string of 0x{:X} bytes too long for 0x{size:X} byte field",
+                val.len()
+            ));
+        }
+        let fill = match padding {
+            Padding::Zero if val.bytes().any(|v| v == 0) => {
+                return Err(eyre!("string has embedded zero"));
+            }
+            Padding::Zero => 0,
+            Padding::Space => b' ',
+        };
+        self.write_all(val.as_bytes())?;
+        self.write_all(&vec![fill; size - val.len()])?;
+        Ok(())
+    }
+
+    /// Write string prefixed with `u8` length
+    fn write_pstring_u8(&mut self, val: &str) -> Result<()> {
+        let size = u8::try_from(val.len()).map_err(|_| prefix_overflow(val, "u8"))?;
+        self.write_u8(size)?;
+        self.write_exact(val.as_bytes())
+    }
+
+    /// Write string prefixed with big-endian `u16` length
+    fn write_string_u16_be(&mut self, val: &str) -> Result<()> {
+        let size = u16::try_from(val.len()).map_err(|_| prefix_overflow(val, "u16"))?;
+        self.write_u16_be(size)?;
+        self.write_exact(val.as_bytes())
+    }
+
+    /// Write string prefixed with little-endian `u16` length
+    fn write_string_u16_le(&mut self, val: &str) -> Result<()> {
+        let size = u16::try_from(val.len()).map_err(|_| prefix_overflow(val, "u16"))?;
+        self.write_u16_le(size)?;
+        self.write_exact(val.as_bytes())
+    }
+
+    /// Write string prefixed with big-endian `u32` length
+    fn write_string_u32_be(&mut self, val: &str) -> Result<()> {
+        let size = u32::try_from(val.len()).map_err(|_| prefix_overflow(val, "u32"))?;
+        self.write_u32_be(size)?;
+        self.write_exact(val.as_bytes())
+    }
+
+    /// Write string prefixed with little-endian `u32` length
+    fn write_string_u32_le(&mut self, val: &str) -> Result<()> {
+        let size = u32::try_from(val.len()).map_err(|_| prefix_overflow(val, "u32"))?;
+        self.write_u32_le(size)?;
+        self.write_exact(val.as_bytes())
+    }
+
+    /// Write string prefixed with length encoded as in [`SafeWrite::write_leb128`]
+    fn write_string_leb128(&mut self, val: &str) -> Result<()> {
+        self.write_leb128(val.len() as u128)?;
+        self.write_exact(val.as_bytes())
+    }
+
+    /// Write string as big-endian UTF-16, without length or terminator
+    fn write_utf16_be(&mut self, val: &str) -> Result<()> {
+        let units: Vec<u16> = val.encode_utf16().collect();
+        self.write_u16_be_slice(&units)
+    }
+
+    /// Write string as big-endian UTF-16, prefixed with `u16` count of code units
+    fn write_utf16_be_prefixed(&mut self, val: &str) -> Result<()> {
+        let units: Vec<u16> = val.encode_utf16().collect();
+        let size = u16::try_from(units.len()).map_err(|_| {
+            eyre!(
+                "string of 0x{:X} UTF-16 units too long for u16 length prefix",
+                units.len()
+            )
+        })?;
+        self.write_u16_be(size)?;
+        self.write_u16_be_slice(&units)
+    }
+
+    /// Write string as zero-terminated big-endian UTF-16, failing if it contains a zero
+    fn write_utf16_be_cstr(&mut self, val: &str) -> Result<()> {
+        if let Some(pos) = val.bytes().position(|v| v == 0) {
+            return Err(eyre!("string has embedded zero at offset 0x{pos:X}"));
+        }
+        self.write_utf16_be(val)?;
+        self.write_u16_be(0)
+    }
+
+    /// Write string as little-endian UTF-16, without length or terminator
+    fn write_utf16_le(&mut self, val: &str) -> Result<()> {
+        let units: Vec<u16> = val.encode_utf16().collect();
+        self.write_u16_le_slice(&units)
+    }
+
+    /// Write string as little-endian UTF-16, prefixed with `u16` count of code units
+    fn write_utf16_le_prefixed(&mut self, val: &str) -> Result<()> {
+        let units: Vec<u16> = val.encode_utf16().collect();
+        let size = u16::try_from(units.len()).map_err(|_| {
+            eyre!(
+                "string of 0x{:X} UTF-16 units too long for u16 length prefix",
+                units.len()
+            )
+        })?;
+        self.write_u16_le(size)?;
+        self.write_u16_le_slice(&units)
+    }
+
+    /// Write string as zero-terminated little-endian UTF-16, failing if it contains a zero
+    fn write_utf16_le_cstr(&mut self, val: &str) -> Result<()> {
+        if let Some(pos) = val.bytes().position(|v| v == 0) {
+            return Err(eyre!("string has embedded zero at offset 0x{pos:X}"));
+        }
+        self.write_utf16_le(val)?;
+        self.write_u16_le(0)
+    }
+
+    /// Write string as Modified UTF-8 (see [`crate::text::encode_mutf8`]), without length
+    fn write_mutf8(&mut self, val: &str) -> Result<()> { self.write_exact(&encode_mutf8(val)) }
+
+    /// Write string as Latin-1, failing on characters above U+00FF
+    fn write_latin1(&mut self, val: &str) -> Result<()> { self.write_exact(&encode_latin1(val)?) }
+
+    /// Write value in format read by [`crate::SafeRead::read_leb128`]: 7-bit groups, most
+    /// significant first, high bit set on all but the last one
+    fn write_leb128(&mut self, val: u128) -> Result<()> {
+        let mut buf = [0u8; 19];
+        let mut pos = buf.len() - 1;
+        let mut rest = val;
+
+        buf[pos] = (rest & 0x7F) as u8;
+        rest >>= 7;
+        while rest != 0 {
+            pos -= 1;
+            buf[pos] = (rest & 0x7F) as u8 | 0x80;
+            rest >>= 7;
+        }
+        self.write_exact(&buf[pos ..])
+    }
 }